@@ -1660,6 +1660,45 @@ extern "C" {
     ) -> c_int;
     pub fn ncrecget(ncid: c_int, recnum: c_long, datap: *mut *mut c_void) -> c_int;
     pub fn ncrecput(ncid: c_int, recnum: c_long, datap: *const *mut c_void) -> c_int;
+    #[cfg(feature = "filters")]
+    pub fn nc_def_var_filter(
+        ncid: c_int,
+        varid: c_int,
+        id: c_uint,
+        nparams: usize,
+        parms: *const c_uint,
+    ) -> c_int;
+    #[cfg(feature = "filters")]
+    pub fn nc_inq_var_filter_ids(
+        ncid: c_int,
+        varid: c_int,
+        nfilters: *mut usize,
+        filterids: *mut c_uint,
+    ) -> c_int;
+    #[cfg(feature = "filters")]
+    pub fn nc_inq_var_filter_info(
+        ncid: c_int,
+        varid: c_int,
+        id: c_uint,
+        nparams: *mut usize,
+        params: *mut c_uint,
+    ) -> c_int;
+    #[cfg(feature = "filters")]
+    pub fn nc_inq_filter_avail(ncid: c_int, id: c_uint) -> c_int;
+    #[cfg(feature = "quantize")]
+    pub fn nc_def_var_quantize(
+        ncid: c_int,
+        varid: c_int,
+        quantize_mode: c_int,
+        nsd: c_int,
+    ) -> c_int;
+    #[cfg(feature = "quantize")]
+    pub fn nc_inq_var_quantize(
+        ncid: c_int,
+        varid: c_int,
+        quantize_modep: *mut c_int,
+        nsdp: *mut c_int,
+    ) -> c_int;
     #[cfg(feature = "memio")]
     pub fn nc_open_mem(
         path: *const c_char,
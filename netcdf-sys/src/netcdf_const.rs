@@ -21,10 +21,17 @@ pub const NC_OPAQUE: ::std::os::raw::c_int = 14;
 pub const NC_ENUM: ::std::os::raw::c_int = 15;
 pub const NC_COMPOUND: ::std::os::raw::c_int = 16;
 pub const NC_FIRSTUSERTYPEID: ::std::os::raw::c_int = 32;
+pub const NC_FILL_BYTE: ::std::os::raw::c_schar = -127;
+pub const NC_FILL_CHAR: ::std::os::raw::c_char = 0;
+pub const NC_FILL_SHORT: ::std::os::raw::c_short = -32767;
 pub const NC_FILL_INT: ::std::os::raw::c_int = -2147483647;
+pub const NC_FILL_FLOAT: f32 = 9.9692099683868690e+36;
+pub const NC_FILL_DOUBLE: f64 = 9.9692099683868690e+36;
 pub const NC_FILL_UBYTE: ::std::os::raw::c_int = 255;
 pub const NC_FILL_USHORT: ::std::os::raw::c_int = 65535;
 pub const NC_FILL_UINT: ::std::os::raw::c_uint = 4294967295;
+pub const NC_FILL_INT64: ::std::os::raw::c_longlong = -9223372036854775806;
+pub const NC_FILL_UINT64: ::std::os::raw::c_ulonglong = 18446744073709551614;
 pub const NC_MAX_BYTE: ::std::os::raw::c_int = 127;
 pub const NC_MIN_BYTE: ::std::os::raw::c_int = -128;
 pub const NC_MAX_CHAR: ::std::os::raw::c_int = 255;
@@ -83,12 +90,29 @@ pub const NC_ENDIAN_LITTLE: ::std::os::raw::c_int = 1;
 pub const NC_ENDIAN_BIG: ::std::os::raw::c_int = 2;
 pub const NC_CHUNKED: ::std::os::raw::c_int = 0;
 pub const NC_CONTIGUOUS: ::std::os::raw::c_int = 1;
+pub const NC_COMPACT: ::std::os::raw::c_int = 2;
+pub const NC_VIRTUAL: ::std::os::raw::c_int = 3;
 pub const NC_NOCHECKSUM: ::std::os::raw::c_int = 0;
 pub const NC_FLETCHER32: ::std::os::raw::c_int = 1;
 pub const NC_NOSHUFFLE: ::std::os::raw::c_int = 0;
 pub const NC_SHUFFLE: ::std::os::raw::c_int = 1;
 pub const NC_MIN_DEFLATE_LEVEL: ::std::os::raw::c_int = 0;
 pub const NC_MAX_DEFLATE_LEVEL: ::std::os::raw::c_int = 9;
+pub const NC_NOQUANTIZE: ::std::os::raw::c_int = 0;
+pub const NC_QUANTIZE_BITGROOM: ::std::os::raw::c_int = 1;
+pub const NC_QUANTIZE_GRANULARBR: ::std::os::raw::c_int = 2;
+pub const NC_QUANTIZE_BITROUND: ::std::os::raw::c_int = 3;
+pub const NC_QUANTIZE_MAX_FLOAT_NSD: ::std::os::raw::c_int = 7;
+pub const NC_QUANTIZE_MAX_DOUBLE_NSD: ::std::os::raw::c_int = 15;
+pub const NC_QUANTIZE_MAX_FLOAT_NSB: ::std::os::raw::c_int = 23;
+pub const NC_QUANTIZE_MAX_DOUBLE_NSB: ::std::os::raw::c_int = 52;
+pub const H5Z_FILTER_DEFLATE: ::std::os::raw::c_uint = 1;
+pub const H5Z_FILTER_SHUFFLE: ::std::os::raw::c_uint = 2;
+pub const H5Z_FILTER_FLETCHER32: ::std::os::raw::c_uint = 3;
+pub const H5Z_FILTER_SZIP: ::std::os::raw::c_uint = 4;
+pub const H5Z_FILTER_BZIP2: ::std::os::raw::c_uint = 307;
+pub const H5Z_FILTER_BLOSC: ::std::os::raw::c_uint = 32001;
+pub const H5Z_FILTER_ZSTD: ::std::os::raw::c_uint = 32015;
 pub const NC_NOERR: ::std::os::raw::c_int = 0;
 pub const NC2_ERR: ::std::os::raw::c_int = -1;
 pub const NC_EBADID: ::std::os::raw::c_int = -33;
@@ -171,6 +195,11 @@ pub const NC_ENOTBUILT: ::std::os::raw::c_int = -128;
 pub const NC_EDISKLESS: ::std::os::raw::c_int = -129;
 pub const NC_ECANTEXTEND: ::std::os::raw::c_int = -130;
 pub const NC_EMPI: ::std::os::raw::c_int = -131;
+pub const NC_EFILTER: ::std::os::raw::c_int = -132;
+pub const NC_ERCFILE: ::std::os::raw::c_int = -133;
+pub const NC_ENULLPAD: ::std::os::raw::c_int = -134;
+pub const NC_EINMEMORY: ::std::os::raw::c_int = -135;
+pub const NC_ENOFILTER: ::std::os::raw::c_int = -136;
 pub const NC4_LAST_ERROR: ::std::os::raw::c_int = -131;
 pub const DIM_WITHOUT_VARIABLE: &[u8; 54_usize] =
     b"This is a netCDF dimension but not a netCDF variable.\x00";
@@ -60,7 +60,7 @@ impl<'a> Attribute<'a> {
         }
         Ok(nelems as _)
     }
-    fn typ(&self) -> error::Result<nc_type> {
+    pub(crate) fn typ(&self) -> error::Result<nc_type> {
         let mut atttype = 0;
         unsafe {
             error::checked(nc_inq_atttype(
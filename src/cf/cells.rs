@@ -0,0 +1,262 @@
+//! Cells of data variables, given by the attributes `bounds` and `cell_methods`
+
+use super::{resolve_variable, string_attribute_of};
+use crate::error;
+use crate::group::Group;
+use crate::variable::Variable;
+use crate::LOCK;
+use netcdf_sys::*;
+use std::fmt;
+
+/// The boundaries of the cells of a coordinate, given by the `bounds`
+/// attribute. Use [`Group::bounds_variable`] to get the variable
+/// holding the vertices of the cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounds {
+    /// Name of the bounds variable
+    pub name: String,
+    /// Number of vertices of each cell (the length of
+    /// the trailing dimension of the bounds variable)
+    pub vertices: usize,
+    pub(crate) ncid: nc_type,
+    pub(crate) varid: nc_type,
+}
+
+/// Identifiers of the dimensions of a variable
+fn dimension_ids(ncid: nc_type, varid: nc_type) -> error::Result<Vec<nc_type>> {
+    let _l = LOCK.lock().unwrap();
+    let mut ndims = 0;
+    unsafe {
+        error::checked(nc_inq_varndims(ncid, varid, &mut ndims))?;
+    }
+    let mut dimids = vec![0; ndims as usize];
+    unsafe {
+        error::checked(nc_inq_vardimid(ncid, varid, dimids.as_mut_ptr()))?;
+    }
+    Ok(dimids)
+}
+
+impl Variable {
+    /// Get the bounds of this coordinate, or `None` if there is no
+    /// `bounds` attribute. The bounds variable must have the dimensions of
+    /// this variable, followed by a dimension for the vertices
+    pub fn bounds(&self) -> error::Result<Option<Bounds>> {
+        let name = match string_attribute_of(self.ncid, self.varid, "bounds")? {
+            Some(name) => name.trim().to_string(),
+            None => return Ok(None),
+        };
+        let (ncid, varid) = resolve_variable(self.ncid, &name)?
+            .ok_or_else(|| error::Error::NotFound(format!("bounds variable {}", name)))?;
+
+        let dimids = dimension_ids(ncid, varid)?;
+        let matching = dimids.len() == self.dimensions.len() + 1
+            && self
+                .dimensions
+                .iter()
+                .zip(&dimids)
+                .all(|(d, &id)| d.id == id);
+        if !matching {
+            return Err(format!(
+                "dimensions of bounds variable {} does not match {}",
+                name, self.name
+            )
+            .into());
+        }
+
+        let mut vertices = 0;
+        unsafe {
+            let _l = LOCK.lock().unwrap();
+            error::checked(nc_inq_dimlen(ncid, *dimids.last().unwrap(), &mut vertices))?;
+        }
+        Ok(Some(Bounds {
+            name,
+            vertices,
+            ncid,
+            varid,
+        }))
+    }
+
+    /// Get the methods used for deriving the values of the
+    /// cells, given by the `cell_methods` attribute
+    pub fn cell_methods(&self) -> error::Result<Vec<CellMethod>> {
+        match string_attribute_of(self.ncid, self.varid, "cell_methods")? {
+            Some(x) => parse_cell_methods(&x),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl Group {
+    /// Get the variable holding the vertices of the
+    /// cells, found by searching all groups in the file
+    pub fn bounds_variable(&self, bounds: &Bounds) -> Option<&Variable> {
+        let root = self.parents().last().unwrap_or(self);
+        root.find_variable(bounds.ncid, bounds.varid)
+    }
+}
+
+/// Spacing of the original data, such as `interval: 1 hour`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interval {
+    /// Length of the interval
+    pub value: f64,
+    /// Unit of the interval
+    pub unit: String,
+}
+
+/// A method for deriving the values of the cells along some
+/// dimensions, such as `time: mean (interval: 1 hour)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellMethod {
+    /// Names of the dimensions (or `area`) the method applies to
+    pub names: Vec<String>,
+    /// The method, such as `mean`, `maximum` or `point`
+    pub method: String,
+    /// Type of the area the method applies to (`where <type>`)
+    pub where_type: Option<String>,
+    /// Type of the area the method is applied over (`where <type1> over <type2>`)
+    pub over_type: Option<String>,
+    /// Climatological statistics within a period (`within days`)
+    pub within: Option<String>,
+    /// Climatological statistics over a period (`over years`)
+    pub over: Option<String>,
+    /// Spacing of the original data
+    pub intervals: Vec<Interval>,
+    /// Additional information
+    pub comment: Option<String>,
+}
+
+/// Parse a `cell_methods` attribute, such as
+/// `time: mean (interval: 1 hour) area: maximum where land`
+pub fn parse_cell_methods(s: &str) -> error::Result<Vec<CellMethod>> {
+    let invalid = || error::Error::from(format!("invalid cell_methods {}", s));
+
+    // Words, where a parenthesised comment is a single word
+    let mut words = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let end = if rest.starts_with('(') {
+            rest.find(')').ok_or_else(invalid)? + 1
+        } else {
+            rest.find(|c: char| c.is_whitespace() || c == '(')
+                .unwrap_or(rest.len())
+        };
+        words.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    let mut methods = Vec::new();
+    let mut words = words.into_iter().peekable();
+    while words.peek().is_some() {
+        let mut names = Vec::new();
+        while let Some(name) = words.peek().and_then(|x| x.strip_suffix(':')) {
+            names.push(name.to_string());
+            words.next();
+        }
+        let method = words.next().ok_or_else(invalid)?;
+        if names.is_empty() || method.starts_with('(') {
+            return Err(invalid());
+        }
+        let mut cell_method = CellMethod {
+            names,
+            method: method.to_string(),
+            where_type: None,
+            over_type: None,
+            within: None,
+            over: None,
+            intervals: Vec::new(),
+            comment: None,
+        };
+
+        while let Some(&word) = words.peek() {
+            let target = match word {
+                "where" if cell_method.where_type.is_none() => &mut cell_method.where_type,
+                "over" if cell_method.where_type.is_some() && cell_method.over_type.is_none() => {
+                    &mut cell_method.over_type
+                }
+                "within" => &mut cell_method.within,
+                "over" => &mut cell_method.over,
+                _ => break,
+            };
+            words.next();
+            match words.next() {
+                Some(x) if !x.ends_with(':') && !x.starts_with('(') => {
+                    *target = Some(x.to_string())
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        if let Some(comment) = words.peek().and_then(|x| x.strip_prefix('(')) {
+            words.next();
+            let comment = comment.trim_end_matches(')').trim();
+            parse_comment(comment, &mut cell_method).ok_or_else(invalid)?;
+        }
+        methods.push(cell_method);
+    }
+    Ok(methods)
+}
+
+/// Parse `interval: <value> <unit>` (repeated), optionally
+/// followed by `comment: <text>`, or some free text
+fn parse_comment(s: &str, cell_method: &mut CellMethod) -> Option<()> {
+    if !s.starts_with("interval:") {
+        if !s.is_empty() {
+            cell_method.comment = Some(s.to_string());
+        }
+        return Some(());
+    }
+    let mut words = s.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "interval:" => {
+                let value = words.next()?.parse().ok()?;
+                let unit = words.next()?.to_string();
+                cell_method.intervals.push(Interval { value, unit });
+            }
+            "comment:" => {
+                cell_method.comment = Some(words.collect::<Vec<_>>().join(" "));
+                break;
+            }
+            _ => return None,
+        }
+    }
+    Some(())
+}
+
+impl fmt::Display for CellMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for name in &self.names {
+            write!(f, "{}: ", name)?;
+        }
+        f.write_str(&self.method)?;
+        if let Some(x) = &self.where_type {
+            write!(f, " where {}", x)?;
+        }
+        if let Some(x) = &self.over_type {
+            write!(f, " over {}", x)?;
+        }
+        if let Some(x) = &self.within {
+            write!(f, " within {}", x)?;
+        }
+        if let Some(x) = &self.over {
+            write!(f, " over {}", x)?;
+        }
+        if self.intervals.is_empty() && self.comment.is_none() {
+            return Ok(());
+        }
+        f.write_str(" (")?;
+        for (i, interval) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "interval: {} {}", interval.value, interval.unit)?;
+        }
+        match &self.comment {
+            Some(x) if self.intervals.is_empty() => f.write_str(x)?,
+            Some(x) => write!(f, " comment: {}", x)?,
+            None => {}
+        }
+        f.write_str(")")
+    }
+}
@@ -0,0 +1,505 @@
+//! Checking files against the most important rules of the CF conventions
+
+use super::string_attribute_of;
+use super::time::{Calendar, TimeUnits};
+use crate::error;
+use crate::file::File;
+use crate::group::Group;
+use crate::variable::Variable;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Severity of an [`Issue`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Probably fine, but could not be verified
+    Warning,
+    /// Violates the conventions
+    Error,
+}
+
+/// A problem found when checking a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// Severity of the problem
+    pub severity: Severity,
+    /// Path of the variable (such as `group/variable`),
+    /// `None` for problems with the file itself
+    pub variable: Option<String>,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match &self.variable {
+            Some(variable) => write!(f, "{}: {}: {}", severity, variable, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+/// Attributes which must be strings
+const STRING_ATTRIBUTES: &[&str] = &[
+    "standard_name",
+    "units",
+    "calendar",
+    "bounds",
+    "cell_methods",
+    "coordinates",
+    "grid_mapping",
+    "flag_meanings",
+];
+
+/// Modifiers which may follow a standard name
+const STANDARD_NAME_MODIFIERS: &[&str] = &[
+    "detection_minimum",
+    "number_of_observations",
+    "standard_error",
+    "status_flag",
+];
+
+/// Checks files against the CF conventions. The table of standard
+/// names is not bundled, so only the form of standard names is checked
+/// unless the table is given through [`Checker::with_standard_name_table`]
+#[derive(Debug, Clone, Default)]
+pub struct Checker {
+    standard_names: Option<HashSet<String>>,
+    table_version: Option<String>,
+}
+
+/// Check a file without a table of standard names, see [`Checker`]
+pub fn check(file: &File) -> Vec<Issue> {
+    Checker::default().check(file)
+}
+
+impl Checker {
+    /// Checker without a table of standard names
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check standard names against the names (including aliases) of
+    /// the official table (`cf-standard-name-table.xml`), unknown
+    /// standard names are then reported as errors
+    pub fn with_standard_name_table(mut self, xml: &str) -> Self {
+        let mut names = HashSet::new();
+        for tag in &["<entry id=\"", "<alias id=\""] {
+            let mut rest = xml;
+            while let Some(start) = rest.find(tag) {
+                rest = &rest[start + tag.len()..];
+                if let Some(end) = rest.find('"') {
+                    names.insert(rest[..end].to_string());
+                }
+            }
+        }
+        self.standard_names = Some(names);
+        self.table_version = xml.find("<version_number>").and_then(|start| {
+            let rest = &xml[start + "<version_number>".len()..];
+            rest.find('<').map(|end| rest[..end].trim().to_string())
+        });
+        self
+    }
+
+    /// Version of the table of standard names given through
+    /// [`Checker::with_standard_name_table`], if it states one
+    pub fn standard_name_table_version(&self) -> Option<&str> {
+        self.table_version.as_deref()
+    }
+
+    /// Check all groups and variables of the file
+    pub fn check(&self, file: &File) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let root = file.root();
+        let conventions = root
+            .attribute("Conventions")
+            .and_then(|x| x.map(|x| x.value()).transpose());
+        match conventions {
+            Ok(Some(crate::attribute::AttrValue::Str(x))) if x.contains("CF-") => {}
+            Ok(None) => issues.push(Issue {
+                severity: Severity::Warning,
+                variable: None,
+                message: "missing global attribute Conventions".into(),
+            }),
+            _ => issues.push(Issue {
+                severity: Severity::Warning,
+                variable: None,
+                message: "global attribute Conventions does not name a CF version".into(),
+            }),
+        }
+        self.check_group(root, "", &mut issues);
+        issues
+    }
+
+    fn check_group(&self, group: &Group, path: &str, issues: &mut Vec<Issue>) {
+        for variable in group.variables() {
+            let name = format!("{}{}", path, variable.name());
+            let mut issue = |severity, message: String| {
+                issues.push(Issue {
+                    severity,
+                    variable: Some(name.clone()),
+                    message,
+                })
+            };
+            if let Err(e) = self.check_variable(variable, &mut issue) {
+                issue(Severity::Error, format!("could not be checked: {}", e));
+            }
+        }
+        for child in group.groups() {
+            self.check_group(child, &format!("{}{}/", path, child.name()), issues);
+        }
+    }
+
+    fn check_variable(
+        &self,
+        variable: &Variable,
+        issue: &mut dyn FnMut(Severity, String),
+    ) -> error::Result<()> {
+        let string = |name| match string_attribute_of(variable.ncid, variable.varid, name) {
+            Err(error::Error::TypeMismatch) => Ok(None),
+            x => x,
+        };
+        for &name in STRING_ATTRIBUTES {
+            if let Err(error::Error::TypeMismatch) =
+                string_attribute_of(variable.ncid, variable.varid, name)
+            {
+                issue(
+                    Severity::Error,
+                    format!("attribute {} must be a string", name),
+                );
+            }
+        }
+
+        if let Some(standard_name) = string("standard_name")? {
+            let mut words = standard_name.split_whitespace();
+            let name = words.next().unwrap_or("");
+            let modifier = words.next();
+            if self
+                .standard_names
+                .as_ref()
+                .is_some_and(|names| !names.contains(name))
+            {
+                let message = match &self.table_version {
+                    Some(version) => {
+                        format!("unknown standard_name {} (table version {})", name, version)
+                    }
+                    None => format!("unknown standard_name {}", name),
+                };
+                issue(Severity::Error, message);
+            }
+            if modifier.is_some_and(|x| !STANDARD_NAME_MODIFIERS.contains(&x))
+                || words.next().is_some()
+            {
+                issue(
+                    Severity::Error,
+                    format!("invalid standard_name modifier in {}", standard_name),
+                );
+            }
+        }
+
+        if let Some(units) = string("units")? {
+            if units.split_whitespace().nth(1) == Some("since") {
+                let calendar = match string("calendar")? {
+                    Some(x) => x.parse::<Calendar>(),
+                    None => Ok(Calendar::default()),
+                };
+                match calendar.and_then(|c| TimeUnits::parse(&units, c)) {
+                    Ok(_) => {}
+                    Err(e) => issue(Severity::Error, format!("{}", e)),
+                }
+            } else if !units_are_valid(&units) {
+                issue(
+                    Severity::Warning,
+                    format!("units {} could not be recognised", units),
+                );
+            }
+        }
+
+        if let Some(attr) = variable.attribute("_FillValue")? {
+            if attr.typ()? != variable.vartype() {
+                issue(
+                    Severity::Error,
+                    "type of _FillValue does not match the variable".into(),
+                );
+            }
+        }
+
+        if variable.dimensions().len() == 1
+            && variable.dimensions()[0].name() == variable.name()
+            && variable.vartype() != netcdf_sys::NC_CHAR
+            && variable.vartype() != netcdf_sys::NC_STRING
+        {
+            check_monotonic(variable, issue)?;
+        }
+
+        if let Err(e) = variable.bounds() {
+            issue(Severity::Error, format!("bounds: {}", e));
+        }
+        if let Err(e) = variable.grid_mapping() {
+            issue(Severity::Error, format!("grid_mapping: {}", e));
+        }
+        if let Err(e) = variable.coordinates() {
+            issue(Severity::Error, format!("coordinates: {}", e));
+        }
+        Ok(())
+    }
+}
+
+/// Coordinate variables must be strictly monotonic and without missing values
+fn check_monotonic(
+    variable: &Variable,
+    issue: &mut dyn FnMut(Severity, String),
+) -> error::Result<()> {
+    let len = variable.dimensions()[0].len();
+    if len == 0 {
+        return Ok(());
+    }
+    let mut values = vec![0.0_f64; len];
+    variable.values_decoded_to(&mut values, None, None)?;
+    if values.iter().any(|x| x.is_nan()) {
+        issue(
+            Severity::Error,
+            "coordinate variable has missing values".into(),
+        );
+        return Ok(());
+    }
+    let increasing = values.windows(2).all(|x| x[0] < x[1]);
+    let decreasing = values.windows(2).all(|x| x[0] > x[1]);
+    if !increasing && !decreasing {
+        issue(
+            Severity::Error,
+            "coordinate variable is not strictly monotonic".into(),
+        );
+    }
+    Ok(())
+}
+
+const UNITS: &[&str] = &[
+    // SI base and derived units
+    "m",
+    "g",
+    "s",
+    "A",
+    "K",
+    "mol",
+    "cd",
+    "rad",
+    "sr",
+    "Hz",
+    "N",
+    "Pa",
+    "J",
+    "W",
+    "C",
+    "V",
+    "F",
+    "ohm",
+    "S",
+    "Wb",
+    "T",
+    "H",
+    "lm",
+    "lx",
+    "Bq",
+    "Gy",
+    "Sv",
+    "kat",
+    "meter",
+    "metre",
+    "gram",
+    "second",
+    "ampere",
+    "kelvin",
+    "mole",
+    "candela",
+    "radian",
+    "steradian",
+    "hertz",
+    "newton",
+    "pascal",
+    "joule",
+    "watt",
+    "coulomb",
+    "volt",
+    "farad",
+    "siemens",
+    "weber",
+    "tesla",
+    "henry",
+    "lumen",
+    "lux",
+    "becquerel",
+    "gray",
+    "sievert",
+    // Other units accepted by udunits
+    "1",
+    "%",
+    "percent",
+    "degC",
+    "deg_C",
+    "celsius",
+    "degree_Celsius",
+    "degree_C",
+    "degF",
+    "fahrenheit",
+    "min",
+    "minute",
+    "h",
+    "hr",
+    "hour",
+    "d",
+    "day",
+    "week",
+    "year",
+    "yr",
+    "a",
+    "common_year",
+    "leap_year",
+    "L",
+    "l",
+    "liter",
+    "litre",
+    "t",
+    "tonne",
+    "bar",
+    "atm",
+    "atmosphere",
+    "mmHg",
+    "torr",
+    "eV",
+    "dB",
+    "bel",
+    "ppm",
+    "ppb",
+    "ppt",
+    "ppv",
+    "ppmv",
+    "ppbv",
+    "degree",
+    "degrees",
+    "arc_degree",
+    "arcminute",
+    "arcsecond",
+    "degree_north",
+    "degree_N",
+    "degreeN",
+    "degrees_north",
+    "degrees_N",
+    "degreesN",
+    "degree_east",
+    "degree_E",
+    "degreeE",
+    "degrees_east",
+    "degrees_E",
+    "degreesE",
+    "degree_true",
+    "degree_T",
+    "degreeT",
+    "knot",
+    "kt",
+    "mile",
+    "nautical_mile",
+    "ft",
+    "foot",
+    "feet",
+    "inch",
+    "in",
+    "yard",
+    "fathom",
+    "au",
+    "parsec",
+    "sverdrup",
+    "gal",
+    "count",
+    "counts",
+    "psu",
+    "PSU",
+    "dBZ",
+    "dbar",
+    "decibar",
+    "mbar",
+    "millibar",
+    "kph",
+    "mph",
+    "cal",
+    "calorie",
+    "erg",
+    "dyn",
+    "dyne",
+    "poise",
+    "stokes",
+    "langley",
+    "DU",
+    "dobson",
+    "Dobson",
+    "Gal",
+    "gn",
+    "kgf",
+    "Ci",
+    "curie",
+    "R",
+    "roentgen",
+    "level",
+    "layer",
+    "sigma_level",
+    "fraction",
+    "unity",
+];
+
+const PREFIXES: &[&str] = &[
+    "Y", "Z", "E", "P", "T", "G", "M", "k", "h", "da", "d", "c", "m", "u", "µ", "n", "p", "f", "a",
+    "z", "y", "yotta", "zetta", "exa", "peta", "tera", "giga", "mega", "kilo", "hecto", "deca",
+    "deka", "deci", "centi", "milli", "micro", "nano", "pico", "femto", "atto", "zepto", "yocto",
+];
+
+/// Whether the unit `name` (without exponent) is known,
+/// possibly with a prefix or in plural form
+fn unit_is_known(name: &str) -> bool {
+    let known = |name: &str| {
+        UNITS.contains(&name)
+            || (name.len() > 2 && name.ends_with('s') && UNITS.contains(&&name[..name.len() - 1]))
+    };
+    known(name)
+        || PREFIXES.iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|rest| !rest.is_empty() && known(rest))
+        })
+}
+
+/// Checks whether `units` looks like a product of known units with
+/// optional exponents and numeric factors, such as `kg m-2 s-1`,
+/// `W.m^-2` or `1e-3`
+fn units_are_valid(units: &str) -> bool {
+    let units = units.trim();
+    if units.is_empty() {
+        return true;
+    }
+    let units = units.replace(" per ", "/").replace("**", "^");
+    units
+        .split(|c: char| c.is_whitespace() || c == '*' || c == '/' || c == '(' || c == ')')
+        .filter(|x| !x.is_empty())
+        .all(|term| {
+            if term.parse::<f64>().is_ok() {
+                return true;
+            }
+            // Products written as m.s-1, where numbers may contain dots
+            term.split('.').all(|factor| {
+                if factor.is_empty() || factor.chars().all(|c| c.is_ascii_digit()) {
+                    return true;
+                }
+                let name = factor.trim_end_matches(|c: char| {
+                    c.is_ascii_digit() || c == '-' || c == '+' || c == '^'
+                });
+                if name.is_empty() {
+                    // Numeric factors such as 10^3
+                    factor.starts_with(|c: char| c.is_ascii_digit())
+                } else {
+                    unit_is_known(name)
+                }
+            })
+        })
+}
@@ -0,0 +1,337 @@
+//! Coordinates of data variables, given by coordinate variables
+//! (with the same name as a dimension) and by the `coordinates` attribute
+
+use super::string_attribute_of;
+use crate::error;
+use crate::group::Group;
+use crate::variable::Variable;
+use crate::LOCK;
+use netcdf_sys::*;
+use std::ffi::CString;
+
+/// Axis of a coordinate
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Axis {
+    /// Longitude, or some other horizontal axis
+    X,
+    /// Latitude, or some other horizontal axis
+    Y,
+    /// Vertical axis
+    Z,
+    /// Time
+    T,
+}
+
+/// How a coordinate is associated with a data variable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CoordinateKind {
+    /// A one-dimensional variable with the same name as
+    /// one of the dimensions of the data variable
+    Dimension,
+    /// A variable named in the `coordinates` attribute
+    Auxiliary,
+}
+
+/// A coordinate of a data variable. Use [`Group::coordinate_variable`]
+/// to get the variable holding the coordinate values
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coordinate {
+    /// Name of the coordinate variable
+    pub name: String,
+    /// The coordinate variable as given by the data variable: the name of
+    /// the dimension, or the reference in the `coordinates` attribute
+    /// (which may be a path, such as `/lat` or `../lon`)
+    pub reference: String,
+    /// How the coordinate is associated with the data variable
+    pub kind: CoordinateKind,
+    /// Axis of the coordinate, if it could be identified
+    pub axis: Option<Axis>,
+    pub(crate) ncid: nc_type,
+    pub(crate) varid: nc_type,
+}
+
+const LATITUDE_UNITS: &[&str] = &[
+    "degrees_north",
+    "degree_north",
+    "degree_n",
+    "degrees_n",
+    "degreen",
+    "degreesn",
+];
+const LONGITUDE_UNITS: &[&str] = &[
+    "degrees_east",
+    "degree_east",
+    "degree_e",
+    "degrees_e",
+    "degreee",
+    "degreese",
+];
+const PRESSURE_UNITS: &[&str] = &[
+    "pa",
+    "hpa",
+    "kpa",
+    "bar",
+    "mbar",
+    "millibar",
+    "decibar",
+    "atm",
+    "atmosphere",
+];
+const VERTICAL_STANDARD_NAMES: &[&str] = &[
+    "altitude",
+    "height",
+    "height_above_geopotential_datum",
+    "height_above_reference_ellipsoid",
+    "height_above_mean_sea_level",
+    "depth",
+    "depth_below_geoid",
+    "air_pressure",
+    "model_level_number",
+    "atmosphere_ln_pressure_coordinate",
+    "atmosphere_sigma_coordinate",
+    "atmosphere_hybrid_sigma_pressure_coordinate",
+    "atmosphere_hybrid_height_coordinate",
+    "atmosphere_sleve_coordinate",
+    "ocean_sigma_coordinate",
+    "ocean_s_coordinate",
+    "ocean_s_coordinate_g1",
+    "ocean_s_coordinate_g2",
+    "ocean_sigma_z_coordinate",
+    "ocean_double_sigma_coordinate",
+];
+
+/// Identify the axis of a variable from the attributes
+/// `axis`, `standard_name`, `units` and `positive`
+fn identify_axis(ncid: nc_type, varid: nc_type) -> error::Result<Option<Axis>> {
+    // Attributes of the wrong type are ignored, as they can
+    // not be used for identifying the axis
+    let attr = |name| -> error::Result<Option<String>> {
+        match string_attribute_of(ncid, varid, name) {
+            Ok(x) => Ok(x.map(|x| x.trim().to_ascii_lowercase())),
+            Err(error::Error::TypeMismatch) => Ok(None),
+            Err(e) => Err(e),
+        }
+    };
+
+    if let Some(axis) = attr("axis")? {
+        match axis.as_str() {
+            "x" => return Ok(Some(Axis::X)),
+            "y" => return Ok(Some(Axis::Y)),
+            "z" => return Ok(Some(Axis::Z)),
+            "t" => return Ok(Some(Axis::T)),
+            _ => {}
+        }
+    }
+    if let Some(name) = attr("standard_name")? {
+        match name.as_str() {
+            "longitude" | "grid_longitude" | "projection_x_coordinate" => return Ok(Some(Axis::X)),
+            "latitude" | "grid_latitude" | "projection_y_coordinate" => return Ok(Some(Axis::Y)),
+            "time" => return Ok(Some(Axis::T)),
+            x if VERTICAL_STANDARD_NAMES.contains(&x) => return Ok(Some(Axis::Z)),
+            _ => {}
+        }
+    }
+    if let Some(units) = attr("units")? {
+        if LONGITUDE_UNITS.contains(&units.as_str()) {
+            return Ok(Some(Axis::X));
+        }
+        if LATITUDE_UNITS.contains(&units.as_str()) {
+            return Ok(Some(Axis::Y));
+        }
+        if units.split_whitespace().nth(1) == Some("since") {
+            return Ok(Some(Axis::T));
+        }
+        if PRESSURE_UNITS.contains(&units.as_str()) {
+            return Ok(Some(Axis::Z));
+        }
+    }
+    if let Some(positive) = attr("positive")? {
+        if positive == "up" || positive == "down" {
+            return Ok(Some(Axis::Z));
+        }
+    }
+    Ok(None)
+}
+
+/// Find a variable by name in the group `ncid`
+fn find_varid(ncid: nc_type, name: &str) -> error::Result<Option<nc_type>> {
+    let cname = CString::new(name).map_err(|_| error::Error::from("name contained interior 0"))?;
+    let mut varid = 0;
+    let _l = LOCK.lock().unwrap();
+    let e = unsafe { nc_inq_varid(ncid, cname.as_ptr(), &mut varid) };
+    if e == NC_ENOTVAR {
+        return Ok(None);
+    }
+    error::checked(e)?;
+    Ok(Some(varid))
+}
+
+/// Name of the variable `varid` in the group `ncid`
+fn variable_name(ncid: nc_type, varid: nc_type) -> error::Result<String> {
+    let mut name = [0_u8; NC_MAX_NAME as usize + 1];
+    let _l = LOCK.lock().unwrap();
+    unsafe {
+        error::checked(nc_inq_varname(ncid, varid, name.as_mut_ptr() as *mut _))?;
+    }
+    let zero_pos = name.iter().position(|&x| x == 0).unwrap_or(name.len());
+    Ok(String::from_utf8_lossy(&name[..zero_pos]).into_owned())
+}
+
+/// Parent of the group `ncid`, `None` for the root group
+fn parent_group(ncid: nc_type) -> error::Result<Option<nc_type>> {
+    let mut parent = 0;
+    let _l = LOCK.lock().unwrap();
+    let e = unsafe { nc_inq_grp_parent(ncid, &mut parent) };
+    if e == NC_ENOGRP {
+        return Ok(None);
+    }
+    error::checked(e)?;
+    Ok(Some(parent))
+}
+
+/// Resolve a reference to a variable as used in the `coordinates`
+/// attribute. Plain names are searched for in the group `ncid` and then
+/// in the ancestors of this group, paths are absolute (starting with `/`)
+/// or relative to the group `ncid`
+pub(super) fn resolve_variable(
+    ncid: nc_type,
+    reference: &str,
+) -> error::Result<Option<(nc_type, nc_type)>> {
+    let pos = match reference.rfind('/') {
+        Some(pos) => pos,
+        None => {
+            let mut group = Some(ncid);
+            while let Some(ncid) = group {
+                if let Some(varid) = find_varid(ncid, reference)? {
+                    return Ok(Some((ncid, varid)));
+                }
+                group = parent_group(ncid)?;
+            }
+            return Ok(None);
+        }
+    };
+
+    let (path, name) = (&reference[..pos], &reference[pos + 1..]);
+    let mut group = ncid;
+    if reference.starts_with('/') {
+        while let Some(parent) = parent_group(group)? {
+            group = parent;
+        }
+    }
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => match parent_group(group)? {
+                Some(parent) => group = parent,
+                None => return Ok(None),
+            },
+            component => {
+                let cname = CString::new(component)
+                    .map_err(|_| error::Error::from("name contained interior 0"))?;
+                let mut child = 0;
+                let _l = LOCK.lock().unwrap();
+                let e = unsafe { nc_inq_grp_ncid(group, cname.as_ptr(), &mut child) };
+                if e == NC_ENOGRP {
+                    return Ok(None);
+                }
+                error::checked(e)?;
+                group = child;
+            }
+        }
+    }
+    Ok(find_varid(group, name)?.map(|varid| (group, varid)))
+}
+
+/// Whether the variable `varid` is one-dimensional along `dimid`
+fn is_along(ncid: nc_type, varid: nc_type, dimid: nc_type) -> error::Result<bool> {
+    let _l = LOCK.lock().unwrap();
+    let mut ndims = 0;
+    unsafe {
+        error::checked(nc_inq_varndims(ncid, varid, &mut ndims))?;
+    }
+    if ndims != 1 {
+        return Ok(false);
+    }
+    let mut id = 0;
+    unsafe {
+        error::checked(nc_inq_vardimid(ncid, varid, &mut id))?;
+    }
+    Ok(id == dimid)
+}
+
+impl Variable {
+    /// Get the coordinates of this variable. These are the coordinate
+    /// variables of the dimensions (in the order of the dimensions),
+    /// followed by the auxiliary coordinates named in the
+    /// `coordinates` attribute
+    ///
+    /// Axes are identified using the attributes `axis`,
+    /// `standard_name`, `units` and `positive`
+    pub fn coordinates(&self) -> error::Result<Vec<Coordinate>> {
+        let mut coordinates = Vec::<Coordinate>::new();
+        for dim in &self.dimensions {
+            let varid = match find_varid(dim.ncid, &dim.name)? {
+                Some(varid) => varid,
+                None => continue,
+            };
+            if (dim.ncid, varid) == (self.ncid, self.varid) || !is_along(dim.ncid, varid, dim.id)? {
+                continue;
+            }
+            coordinates.push(Coordinate {
+                name: dim.name.clone(),
+                reference: dim.name.clone(),
+                kind: CoordinateKind::Dimension,
+                axis: identify_axis(dim.ncid, varid)?,
+                ncid: dim.ncid,
+                varid,
+            });
+        }
+
+        if let Some(names) = string_attribute_of(self.ncid, self.varid, "coordinates")? {
+            for name in names.split_whitespace() {
+                let (ncid, varid) = resolve_variable(self.ncid, name)?.ok_or_else(|| {
+                    error::Error::NotFound(format!("coordinate variable {}", name))
+                })?;
+                if coordinates
+                    .iter()
+                    .any(|c| (c.ncid, c.varid) == (ncid, varid))
+                {
+                    continue;
+                }
+                coordinates.push(Coordinate {
+                    name: variable_name(ncid, varid)?,
+                    reference: name.to_string(),
+                    kind: CoordinateKind::Auxiliary,
+                    axis: identify_axis(ncid, varid)?,
+                    ncid,
+                    varid,
+                });
+            }
+        }
+        Ok(coordinates)
+    }
+
+    /// Get the coordinate along `axis`, preferring
+    /// coordinate variables over auxiliary coordinates
+    pub fn coordinate(&self, axis: Axis) -> error::Result<Option<Coordinate>> {
+        Ok(self
+            .coordinates()?
+            .into_iter()
+            .find(|c| c.axis == Some(axis)))
+    }
+}
+
+impl Group {
+    /// Get the variable holding the values of a coordinate,
+    /// found by searching all groups in the file
+    pub fn coordinate_variable(&self, coordinate: &Coordinate) -> Option<&Variable> {
+        let root = self.parents().last().unwrap_or(self);
+        root.find_variable(coordinate.ncid, coordinate.varid)
+    }
+
+    pub(crate) fn find_variable(&self, ncid: nc_type, varid: nc_type) -> Option<&Variable> {
+        self.variables()
+            .find(|v| v.ncid == ncid && v.varid == varid)
+            .or_else(|| self.groups().find_map(|g| g.find_variable(ncid, varid)))
+    }
+}
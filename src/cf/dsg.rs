@@ -0,0 +1,326 @@
+//! Discrete sampling geometries, where the observations of several
+//! features (such as stations or profiles) are stored as ragged arrays
+
+use super::string_attribute;
+use crate::attribute::AttrValue;
+use crate::error;
+use crate::group::Group;
+use crate::variable::{Numeric, Variable};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// Type of the features of a discrete sampling geometry,
+/// given by the global attribute `featureType`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FeatureType {
+    /// `point`
+    Point,
+    /// `timeSeries`
+    TimeSeries,
+    /// `trajectory`
+    Trajectory,
+    /// `profile`
+    Profile,
+    /// `timeSeriesProfile`
+    TimeSeriesProfile,
+    /// `trajectoryProfile`
+    TrajectoryProfile,
+}
+
+impl FromStr for FeatureType {
+    type Err = error::Error;
+    fn from_str(s: &str) -> error::Result<Self> {
+        Ok(match s.trim().to_ascii_lowercase().as_str() {
+            "point" => Self::Point,
+            "timeseries" => Self::TimeSeries,
+            "trajectory" => Self::Trajectory,
+            "profile" => Self::Profile,
+            "timeseriesprofile" => Self::TimeSeriesProfile,
+            "trajectoryprofile" => Self::TrajectoryProfile,
+            _ => return Err(format!("unknown featureType {}", s).into()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Layout {
+    /// Start of the samples of each feature, followed by the total count
+    Contiguous(Vec<usize>),
+    /// Samples of each feature
+    Indexed(Vec<Vec<usize>>),
+}
+
+/// Observations of several features stored along a single sample
+/// dimension, either contiguously (given by a count variable with the
+/// attribute `sample_dimension`) or indexed (given by an index variable
+/// with the attribute `instance_dimension`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaggedArray {
+    sample_dimension: String,
+    instance_dimension: String,
+    layout: Layout,
+}
+
+impl Group {
+    /// Get the type of the features in this group, given
+    /// by the attribute `featureType` of this group or
+    /// of the closest parent group with this attribute
+    pub fn feature_type(&self) -> error::Result<Option<FeatureType>> {
+        for group in std::iter::once(self).chain(self.parents()) {
+            let value = {
+                let _l = crate::LOCK.lock().unwrap();
+                crate::attribute::Attribute::find_from_name(
+                    group.grpid.unwrap_or(group.ncid),
+                    None,
+                    "featureType",
+                )?
+            };
+            match value.map(|x| x.value()).transpose()? {
+                Some(AttrValue::Str(x)) => return x.parse().map(Some),
+                Some(_) => return Err(error::Error::TypeMismatch),
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the ragged array with observations along `sample_dimension`,
+    /// found by searching for a count or index variable in this group
+    /// and its parents. Returns `None` if there is no such variable
+    pub fn ragged_array(&self, sample_dimension: &str) -> error::Result<Option<RaggedArray>> {
+        let variables = std::iter::once(self)
+            .chain(self.parents())
+            .flat_map(Group::variables);
+        for variable in variables {
+            if variable.dimensions().len() != 1 {
+                continue;
+            }
+            let instance_dimension = &variable.dimensions()[0];
+
+            if string_attribute(variable, "sample_dimension")?.as_deref() == Some(sample_dimension)
+            {
+                let counts = read_integers(variable)?;
+                let mut offsets = Vec::with_capacity(counts.len() + 1);
+                offsets.push(0);
+                for count in counts {
+                    let count = usize::try_from(count)
+                        .map_err(|_| error::Error::from("negative count in ragged array"))?;
+                    offsets.push(offsets.last().unwrap() + count);
+                }
+                let samples = variable.dimension_len(sample_dimension).ok_or_else(|| {
+                    error::Error::NotFound(format!("dimension {}", sample_dimension))
+                })?;
+                if *offsets.last().unwrap() > samples {
+                    return Err(format!(
+                        "counts in {} exceed the length of {}",
+                        variable.name(),
+                        sample_dimension
+                    )
+                    .into());
+                }
+                return Ok(Some(RaggedArray {
+                    sample_dimension: sample_dimension.to_string(),
+                    instance_dimension: instance_dimension.name().to_string(),
+                    layout: Layout::Contiguous(offsets),
+                }));
+            }
+
+            if instance_dimension.name() != sample_dimension {
+                continue;
+            }
+            let instance_dimension = match string_attribute(variable, "instance_dimension")? {
+                Some(x) => x,
+                None => continue,
+            };
+            let instances = variable.dimension_len(&instance_dimension).ok_or_else(|| {
+                error::Error::NotFound(format!("dimension {}", instance_dimension))
+            })?;
+            let mut samples = vec![Vec::new(); instances];
+            for (sample, instance) in read_integers(variable)?.into_iter().enumerate() {
+                // Negative values (such as fill values) mark unassigned samples
+                if let Ok(instance) = usize::try_from(instance) {
+                    samples
+                        .get_mut(instance)
+                        .ok_or(error::Error::IndexMismatch)?
+                        .push(sample);
+                }
+            }
+            return Ok(Some(RaggedArray {
+                sample_dimension: sample_dimension.to_string(),
+                instance_dimension,
+                layout: Layout::Indexed(samples),
+            }));
+        }
+        Ok(None)
+    }
+}
+
+/// Read a one-dimensional integer variable
+fn read_integers(variable: &Variable) -> error::Result<Vec<i64>> {
+    let len = variable.dimensions()[0].len();
+    let mut values = vec![0; len];
+    if len > 0 {
+        variable.relaxed().values_to(&mut values, None, None)?;
+    }
+    Ok(values)
+}
+
+impl Variable {
+    /// Length of the dimension `name`, searching the groups
+    /// containing this variable
+    fn dimension_len(&self, name: &str) -> Option<usize> {
+        let cname = std::ffi::CString::new(name).ok()?;
+        let mut ncid = self.ncid;
+        let _l = crate::LOCK.lock().unwrap();
+        loop {
+            let mut dimid = 0;
+            let mut len = 0;
+            unsafe {
+                if netcdf_sys::nc_inq_dimid(ncid, cname.as_ptr(), &mut dimid)
+                    == netcdf_sys::NC_NOERR
+                    && netcdf_sys::nc_inq_dimlen(ncid, dimid, &mut len) == netcdf_sys::NC_NOERR
+                {
+                    return Some(len);
+                }
+                let mut parent = 0;
+                if netcdf_sys::nc_inq_grp_parent(ncid, &mut parent) != netcdf_sys::NC_NOERR {
+                    return None;
+                }
+                ncid = parent;
+            }
+        }
+    }
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl RaggedArray {
+    /// Name of the dimension of the observations
+    pub fn sample_dimension(&self) -> &str {
+        &self.sample_dimension
+    }
+
+    /// Name of the dimension of the features
+    pub fn instance_dimension(&self) -> &str {
+        &self.instance_dimension
+    }
+
+    /// Number of features
+    pub fn len(&self) -> usize {
+        match &self.layout {
+            Layout::Contiguous(offsets) => offsets.len() - 1,
+            Layout::Indexed(samples) => samples.len(),
+        }
+    }
+
+    /// Whether the observations of each feature are stored contiguously
+    pub fn is_contiguous(&self) -> bool {
+        match self.layout {
+            Layout::Contiguous(_) => true,
+            Layout::Indexed(_) => false,
+        }
+    }
+
+    /// Indices along the sample dimension of the observations of `feature`
+    pub fn samples(&self, feature: usize) -> Option<Vec<usize>> {
+        match &self.layout {
+            Layout::Contiguous(offsets) => {
+                let end = *offsets.get(feature + 1)?;
+                Some((offsets[feature]..end).collect())
+            }
+            Layout::Indexed(samples) => samples.get(feature).cloned(),
+        }
+    }
+
+    /// Fetches the observations of `feature` from `variable`, which must
+    /// have the sample dimension as its first dimension. Any trailing
+    /// dimensions are included for each observation
+    pub fn feature_values<T: Numeric + Copy + Default>(
+        &self,
+        variable: &Variable,
+        feature: usize,
+    ) -> error::Result<Vec<T>> {
+        let rest = self.check_variable(variable)?;
+        let samples = self.samples(feature).ok_or(error::Error::IndexMismatch)?;
+        if let Layout::Contiguous(offsets) = &self.layout {
+            let mut values = vec![T::default(); samples.len() * rest.iter().product::<usize>()];
+            if !values.is_empty() {
+                let mut indices = vec![0; rest.len() + 1];
+                indices[0] = offsets[feature];
+                let mut slice_len = vec![samples.len()];
+                slice_len.extend(&rest);
+                variable.values_to(&mut values, Some(&indices), Some(&slice_len))?;
+            }
+            return Ok(values);
+        }
+        // Only the observations of the feature are read
+        let mut points = Vec::with_capacity(samples.len() * rest.iter().product::<usize>());
+        for &sample in &samples {
+            let mut point = vec![0; rest.len() + 1];
+            point[0] = sample;
+            for _ in 0..rest.iter().product::<usize>() {
+                points.push(point.clone());
+                // Advance with the last dimension varying the fastest
+                for d in (1..point.len()).rev() {
+                    point[d] += 1;
+                    if point[d] < rest[d - 1] {
+                        break;
+                    }
+                    point[d] = 0;
+                }
+            }
+        }
+        let points = points.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        variable.gather(&points)
+    }
+
+    /// Fetches the observations of every feature from `variable`, see
+    /// [`RaggedArray::feature_values`]. The variable is read only once
+    pub fn values<T: Numeric + Copy + Default>(
+        &self,
+        variable: &Variable,
+    ) -> error::Result<Vec<Vec<T>>> {
+        let rest = self.check_variable(variable)?;
+        let all = self.all_values(variable, &rest)?;
+        let stride = rest.iter().product();
+        Ok((0..self.len())
+            .map(|feature| gather(&all, &self.samples(feature).unwrap(), stride))
+            .collect())
+    }
+
+    /// Lengths of the trailing dimensions of `variable`
+    fn check_variable(&self, variable: &Variable) -> error::Result<Vec<usize>> {
+        match variable.dimensions().split_first() {
+            Some((first, rest)) if first.name() == self.sample_dimension => {
+                Ok(rest.iter().map(|d| d.len()).collect())
+            }
+            _ => Err(format!(
+                "{} is not along the sample dimension {}",
+                variable.name(),
+                self.sample_dimension
+            )
+            .into()),
+        }
+    }
+
+    fn all_values<T: Numeric + Copy + Default>(
+        &self,
+        variable: &Variable,
+        rest: &[usize],
+    ) -> error::Result<Vec<T>> {
+        let len = variable.dimensions()[0].len() * rest.iter().product::<usize>();
+        let mut values = vec![T::default(); len];
+        if len > 0 {
+            variable.values_to(&mut values, None, None)?;
+        }
+        Ok(values)
+    }
+}
+
+/// The values at `samples`, each consisting of `stride` elements
+fn gather<T: Copy>(values: &[T], samples: &[usize], stride: usize) -> Vec<T> {
+    samples
+        .iter()
+        .flat_map(|&s| &values[s * stride..(s + 1) * stride])
+        .copied()
+        .collect()
+}
@@ -0,0 +1,154 @@
+//! Flags, given by the attributes `flag_values`,
+//! `flag_masks` and `flag_meanings`
+
+use super::string_attribute;
+use crate::attribute::AttrValue;
+use crate::error;
+use crate::variable::Variable;
+
+/// A single flag of a variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flag {
+    /// Name of the flag, from `flag_meanings`
+    pub meaning: String,
+    /// Value of the flag, from `flag_values`
+    pub value: Option<i64>,
+    /// Bits of the flag, from `flag_masks`
+    pub mask: Option<i64>,
+}
+
+impl Flag {
+    /// Whether the flag is set in `x`. With only a value this is
+    /// `x == value`, with only a mask `x & mask != 0`, and with
+    /// both `x & mask == value`
+    pub fn is_set(&self, x: i64) -> bool {
+        match (self.value, self.mask) {
+            (Some(value), Some(mask)) => x & mask == value,
+            (Some(value), None) => x == value,
+            (None, Some(mask)) => x & mask != 0,
+            (None, None) => false,
+        }
+    }
+}
+
+/// The flags of a variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flags {
+    /// The flags, in the order of `flag_meanings`
+    pub flags: Vec<Flag>,
+}
+
+impl Flags {
+    /// Get a flag by its name
+    pub fn flag(&self, meaning: &str) -> Option<&Flag> {
+        self.flags.iter().find(|f| f.meaning == meaning)
+    }
+
+    /// Names of the flags which are set in `x`
+    pub fn active(&self, x: i64) -> Vec<&str> {
+        self.flags
+            .iter()
+            .filter(|f| f.is_set(x))
+            .map(|f| f.meaning.as_str())
+            .collect()
+    }
+
+    /// Whether the flag named `meaning` is set in `x`,
+    /// `false` if there is no such flag
+    pub fn is_set(&self, x: i64, meaning: &str) -> bool {
+        self.flag(meaning).is_some_and(|f| f.is_set(x))
+    }
+}
+
+/// Integer values of an attribute, `None` for floating
+/// point and string attributes. Unsigned 64-bit integers
+/// are reinterpreted as signed to preserve the bits
+#[allow(clippy::cast_possible_wrap)]
+fn attr_as_i64s(value: &AttrValue) -> Option<Vec<i64>> {
+    Some(match value {
+        AttrValue::Uchar(x) => vec![(*x).into()],
+        AttrValue::Uchars(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Schar(x) => vec![(*x).into()],
+        AttrValue::Schars(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Ushort(x) => vec![(*x).into()],
+        AttrValue::Ushorts(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Short(x) => vec![(*x).into()],
+        AttrValue::Shorts(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Uint(x) => vec![(*x).into()],
+        AttrValue::Uints(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Int(x) => vec![(*x).into()],
+        AttrValue::Ints(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Ulonglong(x) => vec![*x as i64],
+        AttrValue::Ulonglongs(x) => x.iter().map(|&x| x as i64).collect(),
+        AttrValue::Longlong(x) => vec![*x],
+        AttrValue::Longlongs(x) => x.clone(),
+        _ => return None,
+    })
+}
+
+impl Variable {
+    /// Get the flags of this variable, or `None` if
+    /// there is no `flag_meanings` attribute
+    pub fn flags(&self) -> error::Result<Option<Flags>> {
+        let meanings = match string_attribute(self, "flag_meanings")? {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let meanings = meanings.split_whitespace().collect::<Vec<_>>();
+
+        let integers = |name: &str| -> error::Result<Option<Vec<i64>>> {
+            let values = match self.attribute(name)? {
+                Some(attr) => attr.value()?,
+                None => return Ok(None),
+            };
+            let values = attr_as_i64s(&values).ok_or(error::Error::TypeMismatch)?;
+            if values.len() != meanings.len() {
+                return Err(format!(
+                    "{} has {} values, but there are {} flag_meanings",
+                    name,
+                    values.len(),
+                    meanings.len()
+                )
+                .into());
+            }
+            Ok(Some(values))
+        };
+        let values = integers("flag_values")?;
+        let masks = integers("flag_masks")?;
+        if values.is_none() && masks.is_none() {
+            return Err("flag_meanings without flag_values or flag_masks".into());
+        }
+
+        let flags = meanings
+            .iter()
+            .enumerate()
+            .map(|(i, meaning)| Flag {
+                meaning: (*meaning).to_string(),
+                value: values.as_ref().map(|x| x[i]),
+                mask: masks.as_ref().map(|x| x[i]),
+            })
+            .collect();
+        Ok(Some(Flags { flags }))
+    }
+
+    /// Fetches the names of the flags which are set for
+    /// each of the requested elements, see [`Variable::flags`]
+    pub fn flag_meanings(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<Vec<Vec<String>>> {
+        let flags = self
+            .flags()?
+            .ok_or_else(|| error::Error::NotFound("attribute flag_meanings".into()))?;
+        let (indices, slice_len) = self.read_extents(indices, slice_len)?;
+
+        let mut values = vec![0_i64; slice_len.iter().product()];
+        self.relaxed()
+            .values_to(&mut values, Some(&indices), Some(&slice_len))?;
+        Ok(values
+            .into_iter()
+            .map(|x| flags.active(x).into_iter().map(String::from).collect())
+            .collect())
+    }
+}
@@ -0,0 +1,845 @@
+//! Coordinate reference systems, given by the variable
+//! referenced by the `grid_mapping` attribute
+
+use super::{numeric_attribute_of, resolve_variable, string_attribute_of};
+use crate::error;
+use crate::variable::Variable;
+use netcdf_sys::nc_type;
+use std::fmt::Write;
+
+/// Figure of the earth
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Ellipsoid {
+    /// Given by `earth_radius`
+    Sphere {
+        /// Radius in metres
+        radius: f64,
+    },
+    /// Given by `semi_major_axis` and `inverse_flattening`
+    InverseFlattening {
+        /// Semi-major axis in metres
+        semi_major_axis: f64,
+        /// Inverse flattening
+        inverse_flattening: f64,
+    },
+    /// Given by `semi_major_axis` and `semi_minor_axis`
+    SemiMinorAxis {
+        /// Semi-major axis in metres
+        semi_major_axis: f64,
+        /// Semi-minor axis in metres
+        semi_minor_axis: f64,
+    },
+}
+
+impl Ellipsoid {
+    /// The WGS 84 ellipsoid
+    pub const WGS84: Self = Self::InverseFlattening {
+        semi_major_axis: 6_378_137.0,
+        inverse_flattening: 298.257_223_563,
+    };
+
+    /// Semi-major axis and inverse flattening (zero for a sphere)
+    fn axis_and_inverse_flattening(self) -> (f64, f64) {
+        match self {
+            Self::Sphere { radius } => (radius, 0.0),
+            Self::InverseFlattening {
+                semi_major_axis,
+                inverse_flattening,
+            } => (semi_major_axis, inverse_flattening),
+            Self::SemiMinorAxis {
+                semi_major_axis,
+                semi_minor_axis,
+            } if semi_major_axis == semi_minor_axis => (semi_major_axis, 0.0),
+            Self::SemiMinorAxis {
+                semi_major_axis,
+                semi_minor_axis,
+            } => (
+                semi_major_axis,
+                semi_major_axis / (semi_major_axis - semi_minor_axis),
+            ),
+        }
+    }
+}
+
+/// Scale of projections defined either by a standard
+/// parallel or by a scale factor at the origin
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ProjectionScale {
+    /// Given by `standard_parallel`
+    StandardParallel(f64),
+    /// Given by `scale_factor_at_projection_origin`
+    ScaleFactor(f64),
+}
+
+/// A grid mapping defined by the CF conventions, with the parameters
+/// named as the attributes. Angles are given in degrees and lengths in metres
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum GridMapping {
+    /// `latitude_longitude`
+    LatitudeLongitude,
+    /// `rotated_latitude_longitude`
+    RotatedLatitudeLongitude {
+        grid_north_pole_latitude: f64,
+        grid_north_pole_longitude: f64,
+        north_pole_grid_longitude: f64,
+    },
+    /// `polar_stereographic`
+    PolarStereographic {
+        straight_vertical_longitude_from_pole: f64,
+        latitude_of_projection_origin: f64,
+        scale: ProjectionScale,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// `lambert_conformal_conic`, with the same
+    /// standard parallel twice if only one is given
+    LambertConformalConic {
+        standard_parallel: (f64, f64),
+        longitude_of_central_meridian: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// `transverse_mercator`
+    TransverseMercator {
+        scale_factor_at_central_meridian: f64,
+        longitude_of_central_meridian: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// `mercator`
+    Mercator {
+        longitude_of_projection_origin: f64,
+        scale: ProjectionScale,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// `lambert_azimuthal_equal_area`
+    LambertAzimuthalEqualArea {
+        longitude_of_projection_origin: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// `albers_conical_equal_area`, with the same
+    /// standard parallel twice if only one is given
+    AlbersConicalEqualArea {
+        standard_parallel: (f64, f64),
+        longitude_of_central_meridian: f64,
+        latitude_of_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// `stereographic`
+    Stereographic {
+        longitude_of_projection_origin: f64,
+        latitude_of_projection_origin: f64,
+        scale_factor_at_projection_origin: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// `geostationary`
+    Geostationary {
+        longitude_of_projection_origin: f64,
+        perspective_point_height: f64,
+        /// The axis of the sweep angle, `true` for `x` and `false` for `y`
+        sweep_angle_axis_x: bool,
+        false_easting: f64,
+        false_northing: f64,
+    },
+}
+
+/// A coordinate reference system, given by a grid mapping variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crs {
+    /// Name of the grid mapping variable
+    pub name: String,
+    /// The projection
+    pub grid_mapping: GridMapping,
+    /// Figure of the earth, `None` if not specified
+    /// (rendered as [`Ellipsoid::WGS84`])
+    pub ellipsoid: Option<Ellipsoid>,
+}
+
+impl Variable {
+    /// Get the coordinate reference system given by the
+    /// variable referenced by the `grid_mapping` attribute.
+    ///
+    /// For the extended form of the attribute (`crs: lat lon ...`)
+    /// the first grid mapping is used
+    pub fn grid_mapping(&self) -> error::Result<Option<Crs>> {
+        let name = match string_attribute_of(self.ncid, self.varid, "grid_mapping")? {
+            Some(x) => match x.split_whitespace().next() {
+                Some(name) => name.trim_end_matches(':').to_string(),
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+        let (ncid, varid) = resolve_variable(self.ncid, &name)?
+            .ok_or_else(|| error::Error::NotFound(format!("grid mapping variable {}", name)))?;
+        Crs::parse(name, ncid, varid).map(Some)
+    }
+}
+
+impl Crs {
+    fn parse(name: String, ncid: nc_type, varid: nc_type) -> error::Result<Self> {
+        let values = |attr: &str| numeric_attribute_of(ncid, varid, attr);
+        let optional = |attr: &str| -> error::Result<Option<f64>> {
+            Ok(values(attr)?.and_then(|x| x.first().copied()))
+        };
+        let required = |attr: &str| -> error::Result<f64> {
+            optional(attr)?
+                .ok_or_else(|| error::Error::NotFound(format!("attribute {} of {}", attr, name)))
+        };
+        let false_origin = || -> error::Result<(f64, f64)> {
+            Ok((
+                optional("false_easting")?.unwrap_or(0.0),
+                optional("false_northing")?.unwrap_or(0.0),
+            ))
+        };
+        let parallels = || -> error::Result<(f64, f64)> {
+            match values("standard_parallel")?.as_deref() {
+                Some([x]) => Ok((*x, *x)),
+                Some([x, y]) => Ok((*x, *y)),
+                _ => Err(format!("{} must have one or two standard parallels", name).into()),
+            }
+        };
+        let scale = || -> error::Result<ProjectionScale> {
+            if let Some(x) = optional("standard_parallel")? {
+                Ok(ProjectionScale::StandardParallel(x))
+            } else {
+                Ok(ProjectionScale::ScaleFactor(required(
+                    "scale_factor_at_projection_origin",
+                )?))
+            }
+        };
+
+        let mapping_name =
+            string_attribute_of(ncid, varid, "grid_mapping_name")?.ok_or_else(|| {
+                error::Error::NotFound(format!("attribute grid_mapping_name of {}", name))
+            })?;
+        let grid_mapping = match mapping_name.as_str() {
+            "latitude_longitude" => GridMapping::LatitudeLongitude,
+            "rotated_latitude_longitude" => GridMapping::RotatedLatitudeLongitude {
+                grid_north_pole_latitude: required("grid_north_pole_latitude")?,
+                grid_north_pole_longitude: required("grid_north_pole_longitude")?,
+                north_pole_grid_longitude: optional("north_pole_grid_longitude")?.unwrap_or(0.0),
+            },
+            "polar_stereographic" => {
+                let (false_easting, false_northing) = false_origin()?;
+                GridMapping::PolarStereographic {
+                    straight_vertical_longitude_from_pole: required(
+                        "straight_vertical_longitude_from_pole",
+                    )?,
+                    latitude_of_projection_origin: required("latitude_of_projection_origin")?,
+                    scale: scale()?,
+                    false_easting,
+                    false_northing,
+                }
+            }
+            "lambert_conformal_conic" => {
+                let (false_easting, false_northing) = false_origin()?;
+                GridMapping::LambertConformalConic {
+                    standard_parallel: parallels()?,
+                    longitude_of_central_meridian: required("longitude_of_central_meridian")?,
+                    latitude_of_projection_origin: required("latitude_of_projection_origin")?,
+                    false_easting,
+                    false_northing,
+                }
+            }
+            "transverse_mercator" => {
+                let (false_easting, false_northing) = false_origin()?;
+                GridMapping::TransverseMercator {
+                    scale_factor_at_central_meridian: required("scale_factor_at_central_meridian")?,
+                    longitude_of_central_meridian: required("longitude_of_central_meridian")?,
+                    latitude_of_projection_origin: required("latitude_of_projection_origin")?,
+                    false_easting,
+                    false_northing,
+                }
+            }
+            "mercator" => {
+                let (false_easting, false_northing) = false_origin()?;
+                GridMapping::Mercator {
+                    longitude_of_projection_origin: required("longitude_of_projection_origin")?,
+                    scale: scale()?,
+                    false_easting,
+                    false_northing,
+                }
+            }
+            "lambert_azimuthal_equal_area" => {
+                let (false_easting, false_northing) = false_origin()?;
+                GridMapping::LambertAzimuthalEqualArea {
+                    longitude_of_projection_origin: required("longitude_of_projection_origin")?,
+                    latitude_of_projection_origin: required("latitude_of_projection_origin")?,
+                    false_easting,
+                    false_northing,
+                }
+            }
+            "albers_conical_equal_area" => {
+                let (false_easting, false_northing) = false_origin()?;
+                GridMapping::AlbersConicalEqualArea {
+                    standard_parallel: parallels()?,
+                    longitude_of_central_meridian: required("longitude_of_central_meridian")?,
+                    latitude_of_projection_origin: required("latitude_of_projection_origin")?,
+                    false_easting,
+                    false_northing,
+                }
+            }
+            "stereographic" => {
+                let (false_easting, false_northing) = false_origin()?;
+                GridMapping::Stereographic {
+                    longitude_of_projection_origin: required("longitude_of_projection_origin")?,
+                    latitude_of_projection_origin: required("latitude_of_projection_origin")?,
+                    scale_factor_at_projection_origin: required(
+                        "scale_factor_at_projection_origin",
+                    )?,
+                    false_easting,
+                    false_northing,
+                }
+            }
+            "geostationary" => {
+                let (false_easting, false_northing) = false_origin()?;
+                let sweep = match string_attribute_of(ncid, varid, "sweep_angle_axis")? {
+                    Some(x) => x,
+                    None => {
+                        match string_attribute_of(ncid, varid, "fixed_angle_axis")?.as_deref() {
+                            Some("x") => "y".to_string(),
+                            _ => "x".to_string(),
+                        }
+                    }
+                };
+                GridMapping::Geostationary {
+                    longitude_of_projection_origin: required("longitude_of_projection_origin")?,
+                    perspective_point_height: required("perspective_point_height")?,
+                    sweep_angle_axis_x: sweep.trim() == "x",
+                    false_easting,
+                    false_northing,
+                }
+            }
+            x => return Err(format!("unsupported grid_mapping_name {}", x).into()),
+        };
+
+        let ellipsoid = if let Some(radius) = optional("earth_radius")? {
+            Some(Ellipsoid::Sphere { radius })
+        } else if let Some(semi_major_axis) = optional("semi_major_axis")? {
+            if let Some(inverse_flattening) = optional("inverse_flattening")? {
+                Some(Ellipsoid::InverseFlattening {
+                    semi_major_axis,
+                    inverse_flattening,
+                })
+            } else {
+                Some(Ellipsoid::SemiMinorAxis {
+                    semi_major_axis,
+                    semi_minor_axis: optional("semi_minor_axis")?.unwrap_or(semi_major_axis),
+                })
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            name,
+            grid_mapping,
+            ellipsoid,
+        })
+    }
+
+    /// Render as a PROJ string, such as `+proj=stere +lat_0=90 ...`
+    pub fn to_proj(&self) -> String {
+        use GridMapping::*;
+
+        let mut params: Vec<(&str, f64)> = Vec::new();
+        let proj = match self.grid_mapping {
+            LatitudeLongitude => "longlat",
+            RotatedLatitudeLongitude {
+                grid_north_pole_latitude,
+                grid_north_pole_longitude,
+                north_pole_grid_longitude,
+            } => {
+                let mut lon_0 = 180.0 + grid_north_pole_longitude;
+                if lon_0 > 180.0 {
+                    lon_0 -= 360.0;
+                }
+                params.extend(&[
+                    ("o_lat_p", grid_north_pole_latitude),
+                    ("o_lon_p", north_pole_grid_longitude),
+                    ("lon_0", lon_0),
+                ]);
+                "ob_tran +o_proj=longlat"
+            }
+            PolarStereographic {
+                straight_vertical_longitude_from_pole,
+                latitude_of_projection_origin,
+                scale,
+                false_easting,
+                false_northing,
+            } => {
+                params.extend(&[
+                    ("lat_0", latitude_of_projection_origin),
+                    ("lon_0", straight_vertical_longitude_from_pole),
+                ]);
+                params.push(match scale {
+                    ProjectionScale::StandardParallel(x) => ("lat_ts", x),
+                    ProjectionScale::ScaleFactor(x) => ("k_0", x),
+                });
+                params.extend(&[("x_0", false_easting), ("y_0", false_northing)]);
+                "stere"
+            }
+            LambertConformalConic {
+                standard_parallel,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+            }
+            | AlbersConicalEqualArea {
+                standard_parallel,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+            } => {
+                params.extend(&[
+                    ("lat_0", latitude_of_projection_origin),
+                    ("lon_0", longitude_of_central_meridian),
+                    ("lat_1", standard_parallel.0),
+                    ("lat_2", standard_parallel.1),
+                    ("x_0", false_easting),
+                    ("y_0", false_northing),
+                ]);
+                if let LambertConformalConic { .. } = self.grid_mapping {
+                    "lcc"
+                } else {
+                    "aea"
+                }
+            }
+            TransverseMercator {
+                scale_factor_at_central_meridian,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+            } => {
+                params.extend(&[
+                    ("lat_0", latitude_of_projection_origin),
+                    ("lon_0", longitude_of_central_meridian),
+                    ("k_0", scale_factor_at_central_meridian),
+                    ("x_0", false_easting),
+                    ("y_0", false_northing),
+                ]);
+                "tmerc"
+            }
+            Mercator {
+                longitude_of_projection_origin,
+                scale,
+                false_easting,
+                false_northing,
+            } => {
+                params.push(("lon_0", longitude_of_projection_origin));
+                params.push(match scale {
+                    ProjectionScale::StandardParallel(x) => ("lat_ts", x),
+                    ProjectionScale::ScaleFactor(x) => ("k_0", x),
+                });
+                params.extend(&[("x_0", false_easting), ("y_0", false_northing)]);
+                "merc"
+            }
+            LambertAzimuthalEqualArea {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+            } => {
+                params.extend(&[
+                    ("lat_0", latitude_of_projection_origin),
+                    ("lon_0", longitude_of_projection_origin),
+                    ("x_0", false_easting),
+                    ("y_0", false_northing),
+                ]);
+                "laea"
+            }
+            Stereographic {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                scale_factor_at_projection_origin,
+                false_easting,
+                false_northing,
+            } => {
+                params.extend(&[
+                    ("lat_0", latitude_of_projection_origin),
+                    ("lon_0", longitude_of_projection_origin),
+                    ("k_0", scale_factor_at_projection_origin),
+                    ("x_0", false_easting),
+                    ("y_0", false_northing),
+                ]);
+                "stere"
+            }
+            Geostationary {
+                longitude_of_projection_origin,
+                perspective_point_height,
+                sweep_angle_axis_x,
+                false_easting,
+                false_northing,
+            } => {
+                params.extend(&[
+                    ("lon_0", longitude_of_projection_origin),
+                    ("h", perspective_point_height),
+                    ("x_0", false_easting),
+                    ("y_0", false_northing),
+                ]);
+                if sweep_angle_axis_x {
+                    "geos +sweep=x"
+                } else {
+                    "geos +sweep=y"
+                }
+            }
+        };
+
+        let mut s = format!("+proj={}", proj);
+        for (key, value) in params {
+            write!(s, " +{}={}", key, value).unwrap();
+        }
+        match self.ellipsoid {
+            None => s.push_str(" +ellps=WGS84"),
+            Some(Ellipsoid::Sphere { radius }) => write!(s, " +R={}", radius).unwrap(),
+            Some(Ellipsoid::InverseFlattening {
+                semi_major_axis,
+                inverse_flattening,
+            }) => write!(s, " +a={} +rf={}", semi_major_axis, inverse_flattening).unwrap(),
+            Some(Ellipsoid::SemiMinorAxis {
+                semi_major_axis,
+                semi_minor_axis,
+            }) => write!(s, " +a={} +b={}", semi_major_axis, semi_minor_axis).unwrap(),
+        }
+        match self.grid_mapping {
+            LatitudeLongitude => {}
+            RotatedLatitudeLongitude { .. } => s.push_str(" +to_meter=0.0174532925199433"),
+            _ => s.push_str(" +units=m"),
+        }
+        s.push_str(" +no_defs");
+        s
+    }
+
+    /// Render as WKT2 (ISO 19162:2019)
+    pub fn to_wkt2(&self) -> String {
+        use GridMapping::*;
+
+        let (a, rf) = self
+            .ellipsoid
+            .unwrap_or(Ellipsoid::WGS84)
+            .axis_and_inverse_flattening();
+        let datum = format!(
+            "DATUM[\"unknown\",ELLIPSOID[\"unknown\",{},{},LENGTHUNIT[\"metre\",1]]],\
+             PRIMEM[\"Greenwich\",0,ANGLEUNIT[\"degree\",{}]]",
+            a, rf, DEGREE
+        );
+        let ellipsoidal_cs = format!(
+            "CS[ellipsoidal,2],\
+             AXIS[\"longitude\",east,ORDER[1],ANGLEUNIT[\"degree\",{0}]],\
+             AXIS[\"latitude\",north,ORDER[2],ANGLEUNIT[\"degree\",{0}]]",
+            DEGREE
+        );
+
+        let (method, params) = match self.grid_mapping {
+            LatitudeLongitude => {
+                return format!("GEOGCRS[\"{}\",{},{}]", self.name, datum, ellipsoidal_cs);
+            }
+            RotatedLatitudeLongitude {
+                grid_north_pole_latitude,
+                grid_north_pole_longitude,
+                north_pole_grid_longitude,
+            } => {
+                let method = "Pole rotation (netCDF CF convention)";
+                let params = [
+                    (
+                        "Grid north pole latitude (netCDF CF convention)",
+                        grid_north_pole_latitude,
+                    ),
+                    (
+                        "Grid north pole longitude (netCDF CF convention)",
+                        grid_north_pole_longitude,
+                    ),
+                    (
+                        "North pole grid longitude (netCDF CF convention)",
+                        north_pole_grid_longitude,
+                    ),
+                ]
+                .iter()
+                .map(|&(name, value)| parameter(name, value, Unit::Angle, None))
+                .collect::<String>();
+                return format!(
+                    "GEOGCRS[\"{0}\",BASEGEOGCRS[\"unknown\",{1}],\
+                     DERIVINGCONVERSION[\"{2}\",METHOD[\"{2}\"]{3}],{4}]",
+                    self.name, datum, method, params, ellipsoidal_cs
+                );
+            }
+            PolarStereographic {
+                straight_vertical_longitude_from_pole,
+                latitude_of_projection_origin,
+                scale,
+                false_easting,
+                false_northing,
+            } => match scale {
+                ProjectionScale::StandardParallel(x) => (
+                    ("Polar Stereographic (variant B)", Some(9829)),
+                    vec![
+                        ("Latitude of standard parallel", x, Unit::Angle, 8832),
+                        (
+                            "Longitude of origin",
+                            straight_vertical_longitude_from_pole,
+                            Unit::Angle,
+                            8833,
+                        ),
+                        ("False easting", false_easting, Unit::Length, 8806),
+                        ("False northing", false_northing, Unit::Length, 8807),
+                    ],
+                ),
+                ProjectionScale::ScaleFactor(x) => (
+                    ("Polar Stereographic (variant A)", Some(9810)),
+                    vec![
+                        (
+                            "Latitude of natural origin",
+                            latitude_of_projection_origin,
+                            Unit::Angle,
+                            8801,
+                        ),
+                        (
+                            "Longitude of natural origin",
+                            straight_vertical_longitude_from_pole,
+                            Unit::Angle,
+                            8802,
+                        ),
+                        ("Scale factor at natural origin", x, Unit::Scale, 8805),
+                        ("False easting", false_easting, Unit::Length, 8806),
+                        ("False northing", false_northing, Unit::Length, 8807),
+                    ],
+                ),
+            },
+            LambertConformalConic {
+                standard_parallel,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+            }
+            | AlbersConicalEqualArea {
+                standard_parallel,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+            } => (
+                if let LambertConformalConic { .. } = self.grid_mapping {
+                    ("Lambert Conic Conformal (2SP)", Some(9802))
+                } else {
+                    ("Albers Equal Area", Some(9822))
+                },
+                vec![
+                    (
+                        "Latitude of false origin",
+                        latitude_of_projection_origin,
+                        Unit::Angle,
+                        8821,
+                    ),
+                    (
+                        "Longitude of false origin",
+                        longitude_of_central_meridian,
+                        Unit::Angle,
+                        8822,
+                    ),
+                    (
+                        "Latitude of 1st standard parallel",
+                        standard_parallel.0,
+                        Unit::Angle,
+                        8823,
+                    ),
+                    (
+                        "Latitude of 2nd standard parallel",
+                        standard_parallel.1,
+                        Unit::Angle,
+                        8824,
+                    ),
+                    ("Easting at false origin", false_easting, Unit::Length, 8826),
+                    (
+                        "Northing at false origin",
+                        false_northing,
+                        Unit::Length,
+                        8827,
+                    ),
+                ],
+            ),
+            TransverseMercator {
+                scale_factor_at_central_meridian,
+                longitude_of_central_meridian,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+            } => (
+                ("Transverse Mercator", Some(9807)),
+                natural_origin(
+                    latitude_of_projection_origin,
+                    longitude_of_central_meridian,
+                    Some(scale_factor_at_central_meridian),
+                    false_easting,
+                    false_northing,
+                ),
+            ),
+            Mercator {
+                longitude_of_projection_origin,
+                scale,
+                false_easting,
+                false_northing,
+            } => match scale {
+                ProjectionScale::StandardParallel(x) => (
+                    ("Mercator (variant B)", Some(9805)),
+                    vec![
+                        ("Latitude of 1st standard parallel", x, Unit::Angle, 8823),
+                        (
+                            "Longitude of natural origin",
+                            longitude_of_projection_origin,
+                            Unit::Angle,
+                            8802,
+                        ),
+                        ("False easting", false_easting, Unit::Length, 8806),
+                        ("False northing", false_northing, Unit::Length, 8807),
+                    ],
+                ),
+                ProjectionScale::ScaleFactor(x) => (
+                    ("Mercator (variant A)", Some(9804)),
+                    natural_origin(
+                        0.0,
+                        longitude_of_projection_origin,
+                        Some(x),
+                        false_easting,
+                        false_northing,
+                    ),
+                ),
+            },
+            LambertAzimuthalEqualArea {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                false_easting,
+                false_northing,
+            } => (
+                ("Lambert Azimuthal Equal Area", Some(9820)),
+                natural_origin(
+                    latitude_of_projection_origin,
+                    longitude_of_projection_origin,
+                    None,
+                    false_easting,
+                    false_northing,
+                ),
+            ),
+            Stereographic {
+                longitude_of_projection_origin,
+                latitude_of_projection_origin,
+                scale_factor_at_projection_origin,
+                false_easting,
+                false_northing,
+            } => (
+                ("Stereographic", None),
+                natural_origin(
+                    latitude_of_projection_origin,
+                    longitude_of_projection_origin,
+                    Some(scale_factor_at_projection_origin),
+                    false_easting,
+                    false_northing,
+                ),
+            ),
+            Geostationary {
+                longitude_of_projection_origin,
+                perspective_point_height,
+                sweep_angle_axis_x,
+                false_easting,
+                false_northing,
+            } => {
+                let method = if sweep_angle_axis_x {
+                    "Geostationary Satellite (Sweep X)"
+                } else {
+                    "Geostationary Satellite (Sweep Y)"
+                };
+                let params = [
+                    parameter(
+                        "Longitude of natural origin",
+                        longitude_of_projection_origin,
+                        Unit::Angle,
+                        Some(8802),
+                    ),
+                    parameter(
+                        "Satellite Height",
+                        perspective_point_height,
+                        Unit::Length,
+                        None,
+                    ),
+                    parameter("False easting", false_easting, Unit::Length, Some(8806)),
+                    parameter("False northing", false_northing, Unit::Length, Some(8807)),
+                ]
+                .concat();
+                return projected(&self.name, &datum, method, None, &params);
+            }
+        };
+
+        let params = params
+            .into_iter()
+            .map(|(name, value, unit, id)| parameter(name, value, unit, Some(id)))
+            .collect::<String>();
+        projected(&self.name, &datum, method.0, method.1, &params)
+    }
+}
+
+/// Degree in radians, as used by PROJ
+const DEGREE: &str = "0.0174532925199433";
+
+#[derive(Copy, Clone)]
+enum Unit {
+    Angle,
+    Length,
+    Scale,
+}
+
+/// Parameters of projections defined by a natural origin
+fn natural_origin(
+    latitude: f64,
+    longitude: f64,
+    scale_factor: Option<f64>,
+    false_easting: f64,
+    false_northing: f64,
+) -> Vec<(&'static str, f64, Unit, u32)> {
+    let mut params = vec![
+        ("Latitude of natural origin", latitude, Unit::Angle, 8801),
+        ("Longitude of natural origin", longitude, Unit::Angle, 8802),
+    ];
+    if let Some(k) = scale_factor {
+        params.push(("Scale factor at natural origin", k, Unit::Scale, 8805));
+    }
+    params.push(("False easting", false_easting, Unit::Length, 8806));
+    params.push(("False northing", false_northing, Unit::Length, 8807));
+    params
+}
+
+fn parameter(name: &str, value: f64, unit: Unit, id: Option<u32>) -> String {
+    let unit = match unit {
+        Unit::Angle => format!("ANGLEUNIT[\"degree\",{}]", DEGREE),
+        Unit::Length => "LENGTHUNIT[\"metre\",1]".to_string(),
+        Unit::Scale => "SCALEUNIT[\"unity\",1]".to_string(),
+    };
+    let mut s = format!(",PARAMETER[\"{}\",{},{}", name, value, unit);
+    if let Some(id) = id {
+        write!(s, ",ID[\"EPSG\",{}]", id).unwrap();
+    }
+    s.push(']');
+    s
+}
+
+fn projected(name: &str, datum: &str, method: &str, id: Option<u32>, params: &str) -> String {
+    let id = id.map_or_else(String::new, |id| format!(",ID[\"EPSG\",{}]", id));
+    format!(
+        "PROJCRS[\"{}\",BASEGEOGCRS[\"unknown\",{}],\
+         CONVERSION[\"unknown\",METHOD[\"{}\"{}]{}],\
+         CS[Cartesian,2],\
+         AXIS[\"easting (X)\",east,ORDER[1],LENGTHUNIT[\"metre\",1]],\
+         AXIS[\"northing (Y)\",north,ORDER[2],LENGTHUNIT[\"metre\",1]]]",
+        name, datum, method, id, params
+    )
+}
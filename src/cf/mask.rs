@@ -0,0 +1,226 @@
+//! Masking of missing data, using `_FillValue`, `missing_value`,
+//! `valid_min`, `valid_max` and `valid_range`
+
+use super::{attr_as_f64s, default_fill_value, Float, IntoF64};
+use crate::attribute::AttrValue;
+use crate::error;
+use crate::variable::Variable;
+#[cfg(feature = "ndarray")]
+use ndarray::ArrayD;
+use netcdf_sys::*;
+
+/// Describes which values of a variable are missing. All values
+/// are given in the type stored in the file (before unpacking)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Mask {
+    /// Given by `_FillValue`, or the default fill value of the type
+    /// (except for byte and char types) if this attribute is absent
+    pub fill_value: Option<f64>,
+    /// Given by `missing_value`
+    pub missing_values: Vec<f64>,
+    /// Given by `valid_min`, or the first value of `valid_range`
+    pub valid_min: Option<f64>,
+    /// Given by `valid_max`, or the second value of `valid_range`
+    pub valid_max: Option<f64>,
+}
+
+impl Mask {
+    /// Whether the value `x` should be treated as missing
+    pub fn is_masked(&self, x: f64) -> bool {
+        let matches = |y: f64| x == y || (x.is_nan() && y.is_nan());
+        if self.fill_value.is_some_and(matches) {
+            return true;
+        }
+        if self.missing_values.iter().any(|&y| matches(y)) {
+            return true;
+        }
+        self.valid_min.is_some_and(|min| x < min) || self.valid_max.is_some_and(|max| x > max)
+    }
+
+    /// The mask with its values converted to `T` like the values read
+    /// as `T`, so that values which can not be represented exactly in
+    /// `T` (such as the default fill value of `int` in `f32`) still match
+    fn converted<T: Float>(&self) -> Self {
+        let convert = |x: f64| T::from_f64(x).into_f64();
+        Self {
+            fill_value: self.fill_value.map(convert),
+            missing_values: self.missing_values.iter().copied().map(convert).collect(),
+            valid_min: self.valid_min.map(convert),
+            valid_max: self.valid_max.map(convert),
+        }
+    }
+}
+
+fn is_float(value: &AttrValue) -> bool {
+    matches!(
+        value,
+        AttrValue::Float(_) | AttrValue::Floats(_) | AttrValue::Double(_) | AttrValue::Doubles(_)
+    )
+}
+
+impl Variable {
+    /// Get the description of which values are missing in this variable
+    pub fn mask(&self) -> error::Result<Mask> {
+        let attr = |name| -> error::Result<Option<AttrValue>> {
+            match self.attribute(name)? {
+                Some(attr) => Ok(Some(attr.value()?)),
+                None => Ok(None),
+            }
+        };
+        let numbers = |value: &AttrValue| attr_as_f64s(value).ok_or(error::Error::TypeMismatch);
+
+        let fill_value = match attr("_FillValue")? {
+            Some(x) => numbers(&x)?.first().copied(),
+            None => match self.vartype {
+                NC_BYTE | NC_UBYTE | NC_CHAR => None,
+                t => default_fill_value(t),
+            },
+        };
+        let missing_values = match attr("missing_value")? {
+            Some(x) => numbers(&x)?,
+            None => Vec::new(),
+        };
+
+        let packing = self.packing()?;
+        let integer_type = !matches!(self.vartype, NC_FLOAT | NC_DOUBLE);
+        // Valid ranges of packed data may be given in the unpacked type,
+        // these are converted to the type stored in the file. A negative
+        // `scale_factor` swaps the lower and upper bounds
+        let stored = |value: &AttrValue| -> error::Result<(Vec<f64>, bool)> {
+            let x = numbers(value)?;
+            match packing {
+                Some(p) if integer_type && is_float(value) => Ok((
+                    x.into_iter()
+                        .map(|x| (x - p.add_offset) / p.scale_factor)
+                        .collect(),
+                    p.scale_factor < 0.0,
+                )),
+                _ => Ok((x, false)),
+            }
+        };
+
+        let (mut valid_min, mut valid_max) = (None, None);
+        if let Some(x) = attr("valid_range")? {
+            let (x, swapped) = stored(&x)?;
+            if x.len() != 2 {
+                return Err("valid_range must contain two values".into());
+            }
+            if swapped {
+                valid_min = Some(x[1]);
+                valid_max = Some(x[0]);
+            } else {
+                valid_min = Some(x[0]);
+                valid_max = Some(x[1]);
+            }
+        }
+        if let Some(x) = attr("valid_min")? {
+            match stored(&x)? {
+                (x, false) => valid_min = x.first().copied(),
+                (x, true) => valid_max = x.first().copied(),
+            }
+        }
+        if let Some(x) = attr("valid_max")? {
+            match stored(&x)? {
+                (x, false) => valid_max = x.first().copied(),
+                (x, true) => valid_min = x.first().copied(),
+            }
+        }
+
+        Ok(Mask {
+            fill_value,
+            missing_values,
+            valid_min,
+            valid_max,
+        })
+    }
+
+    /// Fetches variable into slice, with missing values
+    /// (as described by [`Variable::mask`]) given as `None`.
+    /// Buffer must be able to hold all the requested elements
+    pub fn values_masked_to<T: IntoF64>(
+        &self,
+        buffer: &mut [Option<T>],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        let mut values = vec![T::default(); buffer.len()];
+        self.values_to(&mut values, indices, slice_len)?;
+
+        let mask = self.mask()?;
+        for (b, x) in buffer.iter_mut().zip(values) {
+            *b = if mask.is_masked(x.into_f64()) {
+                None
+            } else {
+                Some(x)
+            };
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Fetches variable, with missing values (as
+    /// described by [`Variable::mask`]) given as `None`
+    pub fn values_masked<T: IntoF64>(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<ArrayD<Option<T>>> {
+        let values = self.values::<T>(indices, slice_len)?;
+        let mask = self.mask()?;
+        Ok(values.mapv(|x| {
+            if mask.is_masked(x.into_f64()) {
+                None
+            } else {
+                Some(x)
+            }
+        }))
+    }
+
+    /// Fetches variable into slice, decoding the values according
+    /// to the CF conventions. Missing values (as described by
+    /// [`Variable::mask`]) are given as `NaN`, and the remaining
+    /// values are unpacked using `scale_factor` and `add_offset`.
+    /// Buffer must be able to hold all the requested elements
+    pub fn values_decoded_to<T: Float>(
+        &self,
+        buffer: &mut [T],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        self.relaxed().values_to(buffer, indices, slice_len)?;
+        let mask = self.mask()?.converted::<T>();
+        let packing = self.packing()?;
+        for x in buffer.iter_mut() {
+            *x = self.decode(&mask, packing.as_ref(), *x);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Fetches variable, decoding the values according
+    /// to the CF conventions. Missing values (as described by
+    /// [`Variable::mask`]) are given as `NaN`, and the remaining
+    /// values are unpacked using `scale_factor` and `add_offset`
+    pub fn values_decoded<T: Float>(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<ArrayD<T>> {
+        let mut values = self.relaxed().values::<T>(indices, slice_len)?;
+        let mask = self.mask()?.converted::<T>();
+        let packing = self.packing()?;
+        values.mapv_inplace(|x| self.decode(&mask, packing.as_ref(), x));
+        Ok(values)
+    }
+
+    fn decode<T: Float>(&self, mask: &Mask, packing: Option<&super::Packing>, x: T) -> T {
+        let x = x.into_f64();
+        if mask.is_masked(x) {
+            T::from_f64(f64::NAN)
+        } else if let Some(p) = packing {
+            T::from_f64(p.unpack(self.vartype, x))
+        } else {
+            T::from_f64(x)
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Helpers for interpreting variables according to the
+//! [CF conventions](http://cfconventions.org/)
+
+use super::attribute::{AttrValue, Attribute};
+use super::error;
+use super::variable::{Numeric, Variable};
+use netcdf_sys::*;
+
+mod cells;
+mod checker;
+mod coordinates;
+mod dsg;
+mod flags;
+mod grid_mapping;
+mod mask;
+mod packing;
+pub mod time;
+
+pub use cells::*;
+pub use checker::*;
+pub use coordinates::*;
+pub use dsg::*;
+pub use flags::*;
+pub use grid_mapping::*;
+pub use mask::*;
+pub use packing::*;
+
+/// Numeric types which can be compared with the values of attributes
+pub trait IntoF64: Numeric + Copy + Default {
+    /// Convert into a `f64`, possibly losing precision
+    fn into_f64(self) -> f64;
+}
+
+macro_rules! impl_into_f64 {
+    ($($t: ty),*) => {$(
+        impl IntoF64 for $t {
+            #[allow(clippy::cast_precision_loss, clippy::cast_lossless)]
+            fn into_f64(self) -> f64 {
+                self as _
+            }
+        }
+    )*};
+}
+impl_into_f64!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+/// Floating point types which data can be decoded into
+pub trait Float: IntoF64 {
+    /// Convert from a `f64`, possibly losing precision
+    fn from_f64(x: f64) -> Self;
+}
+
+impl Float for f32 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_f64(x: f64) -> Self {
+        x as _
+    }
+}
+
+impl Float for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+}
+
+/// The fill value used by `libnetcdf` for the type `nctype` when
+/// the variable has no `_FillValue` attribute. The values for 64-bit
+/// integers are not exactly representable as `f64`
+#[allow(clippy::cast_precision_loss)]
+pub fn default_fill_value(nctype: nc_type) -> Option<f64> {
+    match nctype {
+        NC_BYTE => Some(NC_FILL_BYTE.into()),
+        NC_CHAR => Some(NC_FILL_CHAR.into()),
+        NC_SHORT => Some(NC_FILL_SHORT.into()),
+        NC_INT => Some(NC_FILL_INT.into()),
+        NC_FLOAT => Some(NC_FILL_FLOAT.into()),
+        NC_DOUBLE => Some(NC_FILL_DOUBLE),
+        NC_UBYTE => Some(NC_FILL_UBYTE.into()),
+        NC_USHORT => Some(NC_FILL_USHORT.into()),
+        NC_UINT => Some(NC_FILL_UINT.into()),
+        NC_INT64 => Some(NC_FILL_INT64 as f64),
+        NC_UINT64 => Some(NC_FILL_UINT64 as f64),
+        _ => None,
+    }
+}
+
+/// Numeric values of an attribute as `f64`,
+/// `None` for string attributes
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn attr_as_f64s(value: &AttrValue) -> Option<Vec<f64>> {
+    Some(match value {
+        AttrValue::Uchar(x) => vec![(*x).into()],
+        AttrValue::Uchars(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Schar(x) => vec![(*x).into()],
+        AttrValue::Schars(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Ushort(x) => vec![(*x).into()],
+        AttrValue::Ushorts(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Short(x) => vec![(*x).into()],
+        AttrValue::Shorts(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Uint(x) => vec![(*x).into()],
+        AttrValue::Uints(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Int(x) => vec![(*x).into()],
+        AttrValue::Ints(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Ulonglong(x) => vec![*x as f64],
+        AttrValue::Ulonglongs(x) => x.iter().map(|&x| x as f64).collect(),
+        AttrValue::Longlong(x) => vec![*x as f64],
+        AttrValue::Longlongs(x) => x.iter().map(|&x| x as f64).collect(),
+        AttrValue::Float(x) => vec![(*x).into()],
+        AttrValue::Floats(x) => x.iter().map(|&x| x.into()).collect(),
+        AttrValue::Double(x) => vec![*x],
+        AttrValue::Doubles(x) => x.clone(),
+        AttrValue::Str(_) => return None,
+    })
+}
+
+/// Numeric attribute of a variable as `f64`s. Gives
+/// `Error::TypeMismatch` for string attributes
+pub(crate) fn numeric_attribute(
+    variable: &Variable,
+    name: &str,
+) -> error::Result<Option<Vec<f64>>> {
+    numeric_attribute_of(variable.ncid, variable.varid, name)
+}
+
+/// Numeric attribute of the variable `varid` in the group `ncid`
+pub(crate) fn numeric_attribute_of(
+    ncid: nc_type,
+    varid: nc_type,
+    name: &str,
+) -> error::Result<Option<Vec<f64>>> {
+    let attr = {
+        let _l = crate::LOCK.lock().unwrap();
+        Attribute::find_from_name(ncid, Some(varid), name)?
+    };
+    match attr {
+        None => Ok(None),
+        Some(attr) => attr_as_f64s(&attr.value()?)
+            .map(Some)
+            .ok_or(error::Error::TypeMismatch),
+    }
+}
+
+/// String attribute of a variable. Gives
+/// `Error::TypeMismatch` for numeric attributes
+pub(crate) fn string_attribute(variable: &Variable, name: &str) -> error::Result<Option<String>> {
+    string_attribute_of(variable.ncid, variable.varid, name)
+}
+
+/// String attribute of the variable `varid` in the group `ncid`
+pub(crate) fn string_attribute_of(
+    ncid: nc_type,
+    varid: nc_type,
+    name: &str,
+) -> error::Result<Option<String>> {
+    let attr = {
+        let _l = crate::LOCK.lock().unwrap();
+        Attribute::find_from_name(ncid, Some(varid), name)?
+    };
+    match attr {
+        None => Ok(None),
+        Some(attr) => match attr.value()? {
+            AttrValue::Str(s) => Ok(Some(s)),
+            _ => Err(error::Error::TypeMismatch),
+        },
+    }
+}
@@ -0,0 +1,175 @@
+//! Packed data, stored as integers together with
+//! the attributes `scale_factor` and `add_offset`
+
+use super::{numeric_attribute, string_attribute, Float};
+use crate::attribute::AttrValue;
+use crate::error;
+use crate::variable::Variable;
+#[cfg(feature = "ndarray")]
+use ndarray::ArrayD;
+use netcdf_sys::*;
+
+/// Parameters for packing and unpacking data
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Packing {
+    /// Multiplied with the stored value when unpacking
+    pub scale_factor: f64,
+    /// Added to the stored value after scaling when unpacking
+    pub add_offset: f64,
+    /// The stored (signed) integers should be interpreted as unsigned
+    /// (given by the attribute `_Unsigned = "true"`)
+    pub unsigned: bool,
+    /// Type of the unpacked data, given by the type of
+    /// `scale_factor` and `add_offset`
+    pub unpacked_type: nc_type,
+}
+
+impl Packing {
+    /// Number of bits of the stored type, used when
+    /// interpreting signed integers as unsigned
+    fn bits(vartype: nc_type) -> Option<i32> {
+        match vartype {
+            NC_BYTE => Some(8),
+            NC_SHORT => Some(16),
+            NC_INT => Some(32),
+            NC_INT64 => Some(64),
+            _ => None,
+        }
+    }
+
+    /// Unpack a stored value of type `vartype`
+    pub fn unpack(&self, vartype: nc_type, x: f64) -> f64 {
+        let x = match Self::bits(vartype) {
+            Some(bits) if self.unsigned && x < 0.0 => x + 2.0_f64.powi(bits),
+            _ => x,
+        };
+        x * self.scale_factor + self.add_offset
+    }
+
+    /// Pack a value into the representation stored as `vartype`,
+    /// rounding to the nearest integer for integer types
+    pub fn pack(&self, vartype: nc_type, x: f64) -> f64 {
+        let x = (x - self.add_offset) / self.scale_factor;
+        match vartype {
+            NC_FLOAT | NC_DOUBLE => x,
+            _ => {
+                let x = x.round();
+                match Self::bits(vartype) {
+                    Some(bits) if self.unsigned && x >= 2.0_f64.powi(bits - 1) => {
+                        x - 2.0_f64.powi(bits)
+                    }
+                    _ => x,
+                }
+            }
+        }
+    }
+}
+
+impl Variable {
+    /// Get the parameters for unpacking the data in this variable,
+    /// or `None` if neither `scale_factor` nor `add_offset` is present
+    pub fn packing(&self) -> error::Result<Option<Packing>> {
+        let scale_factor = self.attribute("scale_factor")?;
+        let add_offset = self.attribute("add_offset")?;
+        if scale_factor.is_none() && add_offset.is_none() {
+            return Ok(None);
+        }
+
+        let mut unpacked_type = self.vartype;
+        for attr in scale_factor.iter().chain(add_offset.iter()) {
+            match attr.value()? {
+                AttrValue::Float(_) | AttrValue::Floats(_) => unpacked_type = NC_FLOAT,
+                AttrValue::Double(_) | AttrValue::Doubles(_) => unpacked_type = NC_DOUBLE,
+                AttrValue::Str(_) => return Err(error::Error::TypeMismatch),
+                _ => {}
+            }
+        }
+
+        let single = |name| -> error::Result<Option<f64>> {
+            Ok(numeric_attribute(self, name)?.and_then(|x| x.first().copied()))
+        };
+        let unsigned = string_attribute(self, "_Unsigned")
+            .unwrap_or(None)
+            .is_some_and(|x| x.eq_ignore_ascii_case("true"));
+
+        Ok(Some(Packing {
+            scale_factor: single("scale_factor")?.unwrap_or(1.0),
+            add_offset: single("add_offset")?.unwrap_or(0.0),
+            unsigned,
+            unpacked_type,
+        }))
+    }
+
+    /// Fetches variable into slice, unpacking the data using
+    /// `scale_factor` and `add_offset`.
+    /// Buffer must be able to hold all the requested elements
+    ///
+    /// Fill values are unpacked as any other value
+    pub fn values_unpacked_to<T: Float>(
+        &self,
+        buffer: &mut [T],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        self.relaxed().values_to(buffer, indices, slice_len)?;
+        if let Some(packing) = self.packing()? {
+            for x in buffer.iter_mut() {
+                *x = T::from_f64(packing.unpack(self.vartype, x.into_f64()));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Fetches variable, unpacking the data using
+    /// `scale_factor` and `add_offset`
+    ///
+    /// Fill values are unpacked as any other value
+    pub fn values_unpacked<T: Float>(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<ArrayD<T>> {
+        let mut values = self.relaxed().values::<T>(indices, slice_len)?;
+        if let Some(packing) = self.packing()? {
+            values.mapv_inplace(|x: T| T::from_f64(packing.unpack(self.vartype, x.into_f64())));
+        }
+        Ok(values)
+    }
+
+    /// Put a slice of values at `indices`, packing the values
+    /// using `scale_factor` and `add_offset` (which must be added
+    /// to the variable before calling this function).
+    ///
+    /// `NaN` is stored as `_FillValue`, or the default fill
+    /// value if this attribute is not present
+    pub fn put_values_packed<T: Float>(
+        &mut self,
+        values: &[T],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        let packing = match self.packing()? {
+            None => return self.with_relaxed(|var| var.put_values(values, indices, slice_len)),
+            Some(p) => p,
+        };
+        let fill = match numeric_attribute(self, "_FillValue")? {
+            Some(x) => x.first().copied(),
+            None => super::default_fill_value(self.vartype),
+        };
+
+        let vartype = self.vartype;
+        let packed = values
+            .iter()
+            .map(|x| {
+                let x = x.into_f64();
+                if x.is_nan() {
+                    fill.unwrap_or(x)
+                } else {
+                    packing.pack(vartype, x)
+                }
+            })
+            .collect::<Vec<_>>();
+        self.with_relaxed(|var| var.put_values(&packed, indices, slice_len))
+    }
+}
@@ -0,0 +1,594 @@
+//! Time coordinates, given by the attribute `units` on the
+//! form `<unit> since <epoch>` together with the attribute `calendar`
+//!
+//! Years are numbered astronomically, the year before 1 is 0
+
+use super::string_attribute;
+use crate::error;
+use crate::variable::Variable;
+use netcdf_sys::*;
+use std::fmt;
+use std::str::FromStr;
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Calendars defined by the CF conventions
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Calendar {
+    /// Mixed Julian/Gregorian calendar, where 1582-10-15 follows
+    /// 1582-10-04 (`standard` or `gregorian`)
+    #[default]
+    Standard,
+    /// Gregorian calendar extended to dates before 1582-10-15
+    /// (`proleptic_gregorian`)
+    ProlepticGregorian,
+    /// Every year has 365 days (`noleap` or `365_day`)
+    NoLeap,
+    /// Every year has 366 days (`all_leap` or `366_day`)
+    AllLeap,
+    /// Every month has 30 days (`360_day`)
+    Day360,
+    /// Julian calendar, every fourth year is a leap year (`julian`)
+    Julian,
+}
+
+/// Julian day number of 1582-10-15 in the Gregorian calendar
+const GREGORIAN_START: i64 = 2_299_161;
+
+impl Calendar {
+    /// Name of the calendar, as used in the `calendar` attribute
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::ProlepticGregorian => "proleptic_gregorian",
+            Self::NoLeap => "noleap",
+            Self::AllLeap => "all_leap",
+            Self::Day360 => "360_day",
+            Self::Julian => "julian",
+        }
+    }
+
+    /// Whether `year` contains a leap day
+    pub fn is_leap_year(self, year: i64) -> bool {
+        let julian = year.rem_euclid(4) == 0;
+        let gregorian = julian && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0);
+        match self {
+            Self::Standard => {
+                if year < 1582 {
+                    julian
+                } else {
+                    gregorian
+                }
+            }
+            Self::ProlepticGregorian => gregorian,
+            Self::Julian => julian,
+            Self::NoLeap | Self::Day360 => false,
+            Self::AllLeap => true,
+        }
+    }
+
+    /// Number of days in `month` (1 to 12) of `year`
+    pub fn days_in_month(self, year: i64, month: u32) -> u32 {
+        if self == Self::Day360 {
+            return 30;
+        }
+        match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if self.is_leap_year(year) => 29,
+            2 => 28,
+            _ => 0,
+        }
+    }
+
+    /// Whether the date exists in this calendar
+    pub fn is_valid(self, t: &DateTime) -> bool {
+        if t.month < 1 || t.month > 12 || t.day < 1 || t.day > self.days_in_month(t.year, t.month) {
+            return false;
+        }
+        if self == Self::Standard && (t.year, t.month) == (1582, 10) && (5..15).contains(&t.day) {
+            return false;
+        }
+        t.hour < 24 && t.minute < 60 && t.second >= 0.0 && t.second < 60.0
+    }
+
+    /// Consecutive numbering of days, assumes the date is valid.
+    /// Returns `None` if the number does not fit in an `i64`
+    fn day_number(self, year: i64, month: u32, day: u32) -> Option<i64> {
+        let (month, day) = (i64::from(month), i64::from(day));
+        let before = |leap: bool| {
+            (1..month)
+                .map(|m| match m {
+                    2 if leap => 29,
+                    2 => 28,
+                    4 | 6 | 9 | 11 => 30,
+                    _ => 31,
+                })
+                .sum::<i64>()
+        };
+        match self {
+            Self::Day360 => year
+                .checked_mul(360)?
+                .checked_add((month - 1) * 30 + day - 1),
+            Self::NoLeap => year.checked_mul(365)?.checked_add(before(false) + day - 1),
+            Self::AllLeap => year.checked_mul(366)?.checked_add(before(true) + day - 1),
+            Self::Julian => julian_day(year, month, day, false),
+            Self::ProlepticGregorian => julian_day(year, month, day, true),
+            Self::Standard => {
+                let gregorian = julian_day(year, month, day, true)?;
+                if gregorian >= GREGORIAN_START {
+                    Some(gregorian)
+                } else {
+                    julian_day(year, month, day, false)
+                }
+            }
+        }
+    }
+
+    /// Inverse of `day_number`
+    fn date(self, n: i64) -> (i64, u32, u32) {
+        let from_year_length = |len: i64, leap: bool| {
+            let year = n.div_euclid(len);
+            let mut day = n.rem_euclid(len) as u32;
+            let mut month = 1;
+            loop {
+                let days = match month {
+                    _ if len == 360 => 30,
+                    2 if leap => 29,
+                    2 => 28,
+                    4 | 6 | 9 | 11 => 30,
+                    _ => 31,
+                };
+                if day < days {
+                    return (year, month, day + 1);
+                }
+                day -= days;
+                month += 1;
+            }
+        };
+        match self {
+            Self::Day360 => from_year_length(360, false),
+            Self::NoLeap => from_year_length(365, false),
+            Self::AllLeap => from_year_length(366, true),
+            Self::Julian => from_julian_day(n, false),
+            Self::ProlepticGregorian => from_julian_day(n, true),
+            Self::Standard => from_julian_day(n, n >= GREGORIAN_START),
+        }
+    }
+
+    /// Microseconds since the start of day zero, assumes the date is
+    /// valid. Returns `None` if the number does not fit in an `i64`
+    fn micros(self, t: &DateTime) -> Option<i64> {
+        let time = (i64::from(t.hour) * 3600 + i64::from(t.minute) * 60) * 1_000_000
+            + (t.second * 1e6).round() as i64;
+        self.day_number(t.year, t.month, t.day)?
+            .checked_mul(MICROS_PER_DAY)?
+            .checked_add(time)
+    }
+
+    fn datetime(self, micros: i64) -> DateTime {
+        let (year, month, day) = self.date(micros.div_euclid(MICROS_PER_DAY));
+        let micros = micros.rem_euclid(MICROS_PER_DAY);
+        DateTime {
+            year,
+            month,
+            day,
+            hour: (micros / 3_600_000_000) as u32,
+            minute: (micros / 60_000_000 % 60) as u32,
+            second: (micros % 60_000_000) as f64 / 1e6,
+        }
+    }
+}
+
+/// Julian day number of a date in the Julian or Gregorian calendar,
+/// or `None` if the number does not fit in an `i64`
+fn julian_day(year: i64, month: i64, day: i64, gregorian: bool) -> Option<i64> {
+    let a = (14 - month) / 12;
+    let y = year.checked_add(4800 - a)?;
+    let m = month + 12 * a - 3;
+    let days = y
+        .checked_mul(365)?
+        .checked_add(y.div_euclid(4))?
+        .checked_add(day + (153 * m + 2) / 5)?;
+    if gregorian {
+        days.checked_sub(y.div_euclid(100))?
+            .checked_add(y.div_euclid(400))?
+            .checked_sub(32045)
+    } else {
+        days.checked_sub(32083)
+    }
+}
+
+/// Date from a Julian day number in the Julian or Gregorian calendar
+fn from_julian_day(n: i64, gregorian: bool) -> (i64, u32, u32) {
+    let (b, c) = if gregorian {
+        let a = n + 32044;
+        let b = (4 * a + 3).div_euclid(146_097);
+        (b, a - (146_097 * b).div_euclid(4))
+    } else {
+        (0, n + 32082)
+    };
+    let d = (4 * c + 3).div_euclid(1461);
+    let e = c - (1461 * d).div_euclid(4);
+    let m = (5 * e + 2) / 153;
+    let day = e - (153 * m + 2) / 5 + 1;
+    let month = m + 3 - 12 * (m / 10);
+    let year = 100 * b + d - 4800 + m / 10;
+    (year, month as u32, day as u32)
+}
+
+impl FromStr for Calendar {
+    type Err = error::Error;
+    fn from_str(s: &str) -> error::Result<Self> {
+        Ok(match s.trim().to_ascii_lowercase().as_str() {
+            "standard" | "gregorian" => Self::Standard,
+            "proleptic_gregorian" => Self::ProlepticGregorian,
+            "noleap" | "365_day" => Self::NoLeap,
+            "all_leap" | "366_day" => Self::AllLeap,
+            "360_day" => Self::Day360,
+            "julian" => Self::Julian,
+            _ => return Err(format!("unknown calendar {}", s).into()),
+        })
+    }
+}
+
+impl fmt::Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A point in time in some calendar. Whether the date
+/// exists depends on the calendar, see [`Calendar::is_valid`]
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct DateTime {
+    /// Year, may be zero or negative
+    pub year: i64,
+    /// Month, from 1 to 12
+    pub month: u32,
+    /// Day of the month, starting from 1
+    pub day: u32,
+    /// Hour, from 0 to 23
+    pub hour: u32,
+    /// Minute, from 0 to 59
+    pub minute: u32,
+    /// Second, including fractions of a second
+    pub second: f64,
+}
+
+impl DateTime {
+    /// Midnight at the start of the given date
+    pub fn new(year: i64, month: u32, day: u32) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0.0,
+        }
+    }
+
+    /// The same date at the given time of day
+    pub fn and_hms(self, hour: u32, minute: u32, second: f64) -> Self {
+        Self {
+            hour,
+            minute,
+            second,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:",
+            self.year, self.month, self.day, self.hour, self.minute
+        )?;
+        if self.second.fract() == 0.0 {
+            write!(f, "{:02}", self.second)
+        } else {
+            write!(f, "{:09.6}", self.second)
+        }
+    }
+}
+
+/// The unit of a time coordinate
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimeUnit {
+    /// `days`, `day` or `d`
+    Days,
+    /// `hours`, `hour`, `hr` or `h`
+    Hours,
+    /// `minutes`, `minute` or `min`
+    Minutes,
+    /// `seconds`, `second`, `sec` or `s`
+    Seconds,
+    /// `milliseconds`, `millisecond`, `msec` or `ms`
+    Milliseconds,
+    /// `microseconds`, `microsecond`, `usec` or `us`
+    Microseconds,
+}
+
+impl TimeUnit {
+    /// Length of the unit in microseconds
+    pub fn micros(self) -> i64 {
+        match self {
+            Self::Days => MICROS_PER_DAY,
+            Self::Hours => 3_600_000_000,
+            Self::Minutes => 60_000_000,
+            Self::Seconds => 1_000_000,
+            Self::Milliseconds => 1000,
+            Self::Microseconds => 1,
+        }
+    }
+
+    /// Name of the unit, as used in the `units` attribute
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Days => "days",
+            Self::Hours => "hours",
+            Self::Minutes => "minutes",
+            Self::Seconds => "seconds",
+            Self::Milliseconds => "milliseconds",
+            Self::Microseconds => "microseconds",
+        }
+    }
+}
+
+impl FromStr for TimeUnit {
+    type Err = error::Error;
+    fn from_str(s: &str) -> error::Result<Self> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "days" | "day" | "d" => Self::Days,
+            "hours" | "hour" | "hrs" | "hr" | "h" => Self::Hours,
+            "minutes" | "minute" | "mins" | "min" => Self::Minutes,
+            "seconds" | "second" | "secs" | "sec" | "s" => Self::Seconds,
+            "milliseconds" | "millisecond" | "msecs" | "msec" | "ms" => Self::Milliseconds,
+            "microseconds" | "microsecond" | "usecs" | "usec" | "us" => Self::Microseconds,
+            _ => return Err(format!("unsupported time unit {}", s).into()),
+        })
+    }
+}
+
+/// Describes how the values of a time coordinate relate to dates
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TimeUnits {
+    /// Unit of the stored values
+    pub unit: TimeUnit,
+    /// Time of the value zero, in UTC
+    pub epoch: DateTime,
+    /// Calendar of the dates
+    pub calendar: Calendar,
+}
+
+impl TimeUnits {
+    /// Parse a `units` attribute on the form `days since 1970-01-01 00:00:00`.
+    /// The time of day and a trailing time zone (such as `UTC`,
+    /// `Z` or `+01:00`) may be omitted
+    pub fn parse(units: &str, calendar: Calendar) -> error::Result<Self> {
+        let invalid = || error::Error::from(format!("invalid time units {}", units));
+
+        let mut words = units.split_whitespace();
+        let unit = words.next().ok_or_else(invalid)?.parse()?;
+        if !words
+            .next()
+            .is_some_and(|x| x.eq_ignore_ascii_case("since"))
+        {
+            return Err(invalid());
+        }
+        let mut rest = words.collect::<Vec<_>>();
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+        // Combined date and time, such as 1970-01-01T00:00:00Z
+        let combined;
+        if let Some(pos) = rest[0].find(['T', 't']) {
+            combined = rest[0].split_at(pos);
+            rest.splice(0..1, vec![combined.0, &combined.1[1..]]);
+        }
+
+        let date = parse_date(rest[0]).ok_or_else(invalid)?;
+        let mut offset_minutes = 0;
+        // Time zone without a time of day, such as 1970-01-01 UTC
+        if rest.len() == 2 && rest[1].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            rest.insert(1, "0:0");
+        }
+        let time = match rest.get(1) {
+            Some(time) => {
+                let (time, zone) = split_zone(time);
+                if let Some(zone) = zone {
+                    offset_minutes = parse_zone(zone).ok_or_else(invalid)?;
+                }
+                parse_time(time).ok_or_else(invalid)?
+            }
+            None => (0, 0, 0.0),
+        };
+        if let Some(zone) = rest.get(2) {
+            offset_minutes = parse_zone(zone).ok_or_else(invalid)?;
+        }
+        if rest.len() > 3 {
+            return Err(invalid());
+        }
+
+        let epoch = DateTime::new(date.0, date.1, date.2).and_hms(time.0, time.1, time.2);
+        if !calendar.is_valid(&epoch) {
+            return Err(format!("epoch {} does not exist in calendar {}", epoch, calendar).into());
+        }
+        let micros = calendar
+            .micros(&epoch)
+            .and_then(|x| x.checked_sub(offset_minutes * 60 * 1_000_000))
+            .ok_or_else(|| error::Error::from(format!("epoch {} is out of range", epoch)))?;
+        let epoch = calendar.datetime(micros);
+
+        Ok(Self {
+            unit,
+            epoch,
+            calendar,
+        })
+    }
+
+    /// The date corresponding to the stored value `x`,
+    /// or `None` if `x` is not finite or too large.
+    /// The result is rounded to the nearest microsecond
+    pub fn decode(&self, x: f64) -> Option<DateTime> {
+        let offset = x * self.unit.micros() as f64;
+        if !offset.is_finite() || offset.abs() > 4e18 {
+            return None;
+        }
+        let micros = self
+            .calendar
+            .micros(&self.epoch)?
+            .checked_add(offset.round() as i64)?;
+        Some(self.calendar.datetime(micros))
+    }
+
+    /// The value to store for the date `t`
+    pub fn encode(&self, t: &DateTime) -> error::Result<f64> {
+        if !self.calendar.is_valid(t) {
+            return Err(format!("{} does not exist in calendar {}", t, self.calendar).into());
+        }
+        let out_of_range = |t: &DateTime| error::Error::from(format!("{} is out of range", t));
+        let micros = self.calendar.micros(t).ok_or_else(|| out_of_range(t))?;
+        let epoch = self
+            .calendar
+            .micros(&self.epoch)
+            .ok_or_else(|| out_of_range(&self.epoch))?;
+        let micros = micros.checked_sub(epoch).ok_or_else(|| out_of_range(t))?;
+        Ok(micros as f64 / self.unit.micros() as f64)
+    }
+}
+
+impl fmt::Display for TimeUnits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} since {}", self.unit.name(), self.epoch)
+    }
+}
+
+fn parse_date(s: &str) -> Option<(i64, u32, u32)> {
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(s) => (-1, s),
+        None => (1, s),
+    };
+    let mut parts = s.split('-');
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next().map_or(Some(1), |x| x.parse().ok())?;
+    let day = parts.next().map_or(Some(1), |x| x.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((sign * year, month, day))
+}
+
+fn parse_time(s: &str) -> Option<(u32, u32, f64)> {
+    let mut parts = s.split(':');
+    let hour = parts.next()?.parse().ok()?;
+    let minute = parts.next().map_or(Some(0), |x| x.parse().ok())?;
+    let second = parts.next().map_or(Some(0.0), |x| x.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((hour, minute, second))
+}
+
+/// Split a trailing zone from a time of day, such as `00:00:00Z`
+fn split_zone(s: &str) -> (&str, Option<&str>) {
+    match s.find(['Z', 'z', '+', '-']) {
+        Some(pos) => (&s[..pos], Some(&s[pos..])),
+        None => (s, None),
+    }
+}
+
+/// Offset from UTC in minutes
+fn parse_zone(s: &str) -> Option<i64> {
+    if ["z", "utc", "gmt"]
+        .iter()
+        .any(|x| s.eq_ignore_ascii_case(x))
+    {
+        return Some(0);
+    }
+    let (sign, s) = if let Some(s) = s.strip_prefix('-') {
+        (-1, s)
+    } else {
+        (1, s.strip_prefix('+').unwrap_or(s))
+    };
+    let (hours, minutes) = match s.find(':') {
+        Some(pos) => (&s[..pos], &s[pos + 1..]),
+        None if s.len() == 4 => s.split_at(2),
+        None => (s, "0"),
+    };
+    let hours = hours.parse::<i64>().ok()?;
+    let minutes = minutes.parse::<i64>().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+impl Variable {
+    /// Get the description of a time coordinate, given by the attributes
+    /// `units` and `calendar` (defaults to `standard`). Returns `None`
+    /// if the units are not on the form `<unit> since <epoch>`
+    pub fn time_units(&self) -> error::Result<Option<TimeUnits>> {
+        let units = match string_attribute(self, "units")? {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        if !units
+            .split_whitespace()
+            .nth(1)
+            .is_some_and(|x| x.eq_ignore_ascii_case("since"))
+        {
+            return Ok(None);
+        }
+        let calendar = match string_attribute(self, "calendar")? {
+            Some(x) => x.parse()?,
+            None => Calendar::default(),
+        };
+        TimeUnits::parse(&units, calendar).map(Some)
+    }
+
+    /// Fetches the values of a time coordinate as dates,
+    /// where missing values (see [`Variable::mask`]) are given as `None`.
+    /// The values are unpacked using `scale_factor` and `add_offset`
+    pub fn times(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<Vec<Option<DateTime>>> {
+        let units = self
+            .time_units()?
+            .ok_or_else(|| error::Error::from("not a time coordinate"))?;
+        let (indices, slice_len) = self.read_extents(indices, slice_len)?;
+
+        let mut values = vec![0.0_f64; slice_len.iter().product()];
+        self.values_decoded_to(&mut values, Some(&indices), Some(&slice_len))?;
+        Ok(values.into_iter().map(|x| units.decode(x)).collect())
+    }
+
+    /// Put dates into a time coordinate, encoding them using the
+    /// attributes `units` and `calendar` (which must be added to the
+    /// variable before calling this function). Values are rounded
+    /// to the nearest integer for variables of integer type
+    pub fn put_times(
+        &mut self,
+        values: &[DateTime],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        let units = self
+            .time_units()?
+            .ok_or_else(|| error::Error::from("not a time coordinate"))?;
+        let round = !matches!(self.vartype, NC_FLOAT | NC_DOUBLE);
+        let values = values
+            .iter()
+            .map(|t| {
+                let x = units.encode(t)?;
+                Ok(if round { x.round() } else { x })
+            })
+            .collect::<error::Result<Vec<_>>>()?;
+        self.with_relaxed(|var| var.put_values(&values, indices, slice_len))
+    }
+}
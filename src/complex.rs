@@ -0,0 +1,369 @@
+//! Complex numbers, stored either as a compound type with a real and an
+//! imaginary member (as written by h5py and nc-complex), or as a floating
+//! point variable with a trailing dimension of length 2, named following
+//! nc-complex
+
+use super::cf::Float;
+use super::error;
+use super::group::Group;
+use super::variable::Variable;
+use super::LOCK;
+#[cfg(feature = "ndarray")]
+use ndarray::ArrayD;
+use netcdf_sys::*;
+pub use num_complex::Complex;
+use std::ffi::CString;
+
+/// Name of the trailing dimension used by [`ComplexLayout::Dimension`]
+/// when adding variables, following nc-complex
+pub const COMPLEX_DIMENSION: &str = "_pfnc_complex";
+
+/// Names of the trailing dimensions recognised as holding the real and
+/// the imaginary part, following nc-complex
+const COMPLEX_DIMENSION_NAMES: [&str; 3] = [COMPLEX_DIMENSION, "complex", "ri"];
+
+/// How the complex numbers of a variable are stored
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ComplexLayout {
+    /// A compound type with a real (`r`) and an imaginary (`i`) member
+    Compound,
+    /// A trailing dimension of length 2, holding
+    /// the real and the imaginary part
+    Dimension,
+}
+
+/// Name of the compound type for complex numbers with parts of `nctype`
+fn compound_name(nctype: nc_type) -> &'static str {
+    if nctype == NC_FLOAT {
+        "_PFNC_FLOAT_COMPLEX_TYPE"
+    } else {
+        "_PFNC_DOUBLE_COMPLEX_TYPE"
+    }
+}
+
+/// The type of the parts if `xtype` is a compound type holding a complex
+/// number, with two floating point members whose names start with `r`
+/// and `i`. Must be called while holding the lock
+fn compound_part_type(ncid: nc_type, xtype: nc_type) -> error::Result<Option<nc_type>> {
+    if xtype < NC_FIRSTUSERTYPEID {
+        return Ok(None);
+    }
+    let mut size = 0;
+    let mut nfields = 0;
+    let mut class = 0;
+    unsafe {
+        error::checked(nc_inq_user_type(
+            ncid,
+            xtype,
+            std::ptr::null_mut(),
+            &mut size,
+            std::ptr::null_mut(),
+            &mut nfields,
+            &mut class,
+        ))?;
+    }
+    if class != NC_COMPOUND || nfields != 2 {
+        return Ok(None);
+    }
+
+    let mut part = None;
+    for (fieldid, prefix) in [b'r', b'i'].iter().enumerate() {
+        let mut name = [0_u8; NC_MAX_NAME as usize + 1];
+        let mut offset = 0;
+        let mut fieldtype = 0;
+        let mut ndims = 0;
+        unsafe {
+            error::checked(nc_inq_compound_field(
+                ncid,
+                xtype,
+                fieldid as _,
+                name.as_mut_ptr() as *mut _,
+                &mut offset,
+                &mut fieldtype,
+                &mut ndims,
+                std::ptr::null_mut(),
+            ))?;
+        }
+        let partsize = match fieldtype {
+            NC_FLOAT => 4,
+            NC_DOUBLE => 8,
+            _ => return Ok(None),
+        };
+        if ndims != 0
+            || offset != fieldid * partsize
+            || size != 2 * partsize
+            || !name[0].eq_ignore_ascii_case(prefix)
+            || part.is_some_and(|p| p != fieldtype)
+        {
+            return Ok(None);
+        }
+        part = Some(fieldtype);
+    }
+    Ok(part)
+}
+
+impl Variable {
+    /// Find how complex numbers are stored in this variable, or `None`
+    /// if it does not hold complex numbers. Floating point variables are
+    /// considered complex if the last dimension has length 2 and is named
+    /// [`COMPLEX_DIMENSION`], `complex` or `ri`, so that other variables
+    /// with a dimension of length 2 (such as cell bounds) are not
+    pub fn complex_layout(&self) -> error::Result<Option<ComplexLayout>> {
+        Ok(self.complex_storage()?.map(|(layout, _)| layout))
+    }
+
+    /// The layout and the type of the parts
+    fn complex_storage(&self) -> error::Result<Option<(ComplexLayout, nc_type)>> {
+        match self.vartype {
+            NC_FLOAT | NC_DOUBLE => Ok(match self.dimensions.last() {
+                Some(d) if d.len() == 2 && COMPLEX_DIMENSION_NAMES.contains(&d.name()) => {
+                    Some((ComplexLayout::Dimension, self.vartype))
+                }
+                _ => None,
+            }),
+            xtype => {
+                let _l = LOCK.lock().unwrap();
+                Ok(compound_part_type(self.ncid, xtype)?.map(|p| (ComplexLayout::Compound, p)))
+            }
+        }
+    }
+
+    /// Extents of the variable, with the trailing
+    /// dimension of `ComplexLayout::Dimension` included
+    fn complex_extents(
+        &self,
+        layout: ComplexLayout,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+        nvalues: Option<usize>,
+    ) -> error::Result<(Vec<usize>, Vec<usize>)> {
+        match (layout, nvalues) {
+            (ComplexLayout::Dimension, _) => self.trailing_extents(indices, slice_len, nvalues),
+            (ComplexLayout::Compound, None) => self.read_extents(indices, slice_len),
+            (ComplexLayout::Compound, Some(n)) => self.put_extents(n, indices, slice_len),
+        }
+    }
+
+    /// Fetches complex numbers into slice, see [`Variable::complex_layout`].
+    /// Buffer must be able to hold all the requested elements.
+    ///
+    /// For `ComplexLayout::Dimension`, `indices` and `slice_len`
+    /// apply to the dimensions preceding the trailing dimension
+    pub fn complex_values_to<T: Float>(
+        &self,
+        buffer: &mut [Complex<T>],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        let (layout, part) = self.complex_storage()?.ok_or(error::Error::TypeMismatch)?;
+        let (indices, slice_len) = self.complex_extents(layout, indices, slice_len, None)?;
+        let mut len = slice_len.iter().product::<usize>();
+        if layout == ComplexLayout::Dimension {
+            len /= 2;
+        }
+        if buffer.len() < len {
+            return Err(error::Error::BufferLen(buffer.len(), len));
+        }
+        let buffer = &mut buffer[..len];
+
+        match (layout, part) {
+            (ComplexLayout::Dimension, _) => {
+                let mut parts = vec![T::default(); 2 * len];
+                self.values_to(&mut parts, Some(&indices), Some(&slice_len))?;
+                for (b, p) in buffer.iter_mut().zip(parts.chunks(2)) {
+                    *b = Complex::new(p[0], p[1]);
+                }
+                Ok(())
+            }
+            (ComplexLayout::Compound, NC_FLOAT) => {
+                self.compound_values_to::<f32, T>(buffer, &indices, &slice_len)
+            }
+            (ComplexLayout::Compound, _) => {
+                self.compound_values_to::<f64, T>(buffer, &indices, &slice_len)
+            }
+        }
+    }
+
+    /// Reads a compound variable with parts of type `S`
+    fn compound_values_to<S: Float, T: Float>(
+        &self,
+        buffer: &mut [Complex<T>],
+        indices: &[usize],
+        slice_len: &[usize],
+    ) -> error::Result<()> {
+        let mut values = vec![Complex::<S>::default(); buffer.len()];
+        {
+            let _l = LOCK.lock().unwrap();
+            unsafe {
+                error::checked(nc_get_vara(
+                    self.ncid,
+                    self.varid,
+                    indices.as_ptr(),
+                    slice_len.as_ptr(),
+                    values.as_mut_ptr() as *mut _,
+                ))?;
+            }
+        }
+        for (b, v) in buffer.iter_mut().zip(values) {
+            *b = Complex::new(T::from_f64(v.re.into_f64()), T::from_f64(v.im.into_f64()));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Fetches complex numbers, see [`Variable::complex_values_to`]
+    pub fn complex_values<T: Float>(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<ArrayD<Complex<T>>> {
+        let (layout, _) = self.complex_storage()?.ok_or(error::Error::TypeMismatch)?;
+        let (_, mut shape) = self.complex_extents(layout, indices, slice_len, None)?;
+        if layout == ComplexLayout::Dimension {
+            shape.pop();
+        }
+        let mut values = vec![Complex::default(); shape.iter().product()];
+        self.complex_values_to(&mut values, indices, slice_len)?;
+        Ok(ArrayD::from_shape_vec(shape, values).unwrap())
+    }
+
+    /// Put complex numbers into the variable, see
+    /// [`Variable::complex_values_to`] for the meaning of
+    /// `indices` and `slice_len`
+    pub fn put_complex_values<T: Float>(
+        &mut self,
+        values: &[Complex<T>],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        let (layout, part) = self.complex_storage()?.ok_or(error::Error::TypeMismatch)?;
+        let (indices, slice_len) =
+            self.complex_extents(layout, indices, slice_len, Some(values.len()))?;
+
+        match (layout, part) {
+            (ComplexLayout::Dimension, _) => {
+                let mut parts = Vec::with_capacity(2 * values.len());
+                for v in values {
+                    parts.push(v.re);
+                    parts.push(v.im);
+                }
+                self.put_values(&parts, Some(&indices), Some(&slice_len))
+            }
+            (ComplexLayout::Compound, NC_FLOAT) => {
+                self.put_compound_values::<f32, T>(values, &indices, &slice_len)
+            }
+            (ComplexLayout::Compound, _) => {
+                self.put_compound_values::<f64, T>(values, &indices, &slice_len)
+            }
+        }
+    }
+
+    /// Writes a compound variable with parts of type `S`
+    fn put_compound_values<S: Float, T: Float>(
+        &mut self,
+        values: &[Complex<T>],
+        indices: &[usize],
+        slice_len: &[usize],
+    ) -> error::Result<()> {
+        let values = values
+            .iter()
+            .map(|v| Complex::new(S::from_f64(v.re.into_f64()), S::from_f64(v.im.into_f64())))
+            .collect::<Vec<_>>();
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_put_vara(
+                self.ncid,
+                self.varid,
+                indices.as_ptr(),
+                slice_len.as_ptr(),
+                values.as_ptr() as *const _,
+            ))
+        }
+    }
+}
+
+impl Group {
+    /// Adds a variable for complex numbers with parts of type `T`.
+    ///
+    /// Using `ComplexLayout::Compound` requires a netCDF-4 file, and
+    /// defines the compound type `_PFNC_FLOAT_COMPLEX_TYPE` or
+    /// `_PFNC_DOUBLE_COMPLEX_TYPE` in this group unless it exists already.
+    /// Using `ComplexLayout::Dimension` appends the dimension
+    /// [`COMPLEX_DIMENSION`] to `dims`, which is likewise created if needed
+    pub fn add_complex_variable<T: Float>(
+        &mut self,
+        name: &str,
+        dims: &[&str],
+        layout: ComplexLayout,
+    ) -> error::Result<&mut Variable> {
+        if self.variable(name).is_some() {
+            return Err(error::Error::AlreadyExists("variable".into()));
+        }
+
+        if layout == ComplexLayout::Dimension {
+            let existing = self
+                .dimension(COMPLEX_DIMENSION)
+                .or_else(|| self.parents().find_map(|p| p.dimension(COMPLEX_DIMENSION)));
+            match existing {
+                Some(d) if d.len() != 2 || d.is_unlimited() => {
+                    return Err(error::Error::AlreadyExists(format!(
+                        "dimension {}",
+                        COMPLEX_DIMENSION
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    self.add_dimension(COMPLEX_DIMENSION, 2)?;
+                }
+            }
+            let mut dims = dims.to_vec();
+            dims.push(COMPLEX_DIMENSION);
+            return self.add_variable::<T>(name, &dims);
+        }
+
+        let ncid = self.grpid.unwrap_or(self.ncid);
+        let xtype = {
+            let _l = LOCK.lock().unwrap();
+            complex_type::<T>(ncid)?
+        };
+        let d = self.find_dimensions(dims)?;
+        let var = Variable::new(ncid, name, d, xtype)?;
+
+        self.variables.push(var);
+        Ok(self.variable_mut(name).unwrap())
+    }
+}
+
+/// Finds or defines the compound type for complex numbers with
+/// parts of type `T`. Must be called while holding the lock
+fn complex_type<T: Float>(ncid: nc_type) -> error::Result<nc_type> {
+    let name = compound_name(T::NCTYPE);
+    let cname = CString::new(name).unwrap();
+    let mut xtype = 0;
+    if unsafe { nc_inq_typeid(ncid, cname.as_ptr(), &mut xtype) } == NC_NOERR {
+        if compound_part_type(ncid, xtype)? == Some(T::NCTYPE) {
+            return Ok(xtype);
+        }
+        return Err(error::Error::AlreadyExists(format!("type {}", name)));
+    }
+
+    let size = std::mem::size_of::<T>();
+    unsafe {
+        error::checked(nc_def_compound(ncid, 2 * size, cname.as_ptr(), &mut xtype))?;
+        error::checked(nc_insert_compound(
+            ncid,
+            xtype,
+            b"r\0".as_ptr() as *const _,
+            0,
+            T::NCTYPE,
+        ))?;
+        error::checked(nc_insert_compound(
+            ncid,
+            xtype,
+            b"i\0".as_ptr() as *const _,
+            size,
+            T::NCTYPE,
+        ))?;
+    }
+    Ok(xtype)
+}
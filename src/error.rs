@@ -45,6 +45,14 @@ pub enum Error {
     Conversion(TryFromIntError),
     /// Identifier belongs to another dataset
     WrongDataset,
+    /// Requested rank does not match the rank of the variable
+    RankMismatch(usize, usize),
+    /// Positions of the values which could not be
+    /// represented in the target type
+    OutOfRange(Vec<usize>),
+    /// The filter with this id is needed to read the
+    /// variable, but is not available (missing plugin)
+    MissingFilter(u32),
 }
 
 impl std::error::Error for Error {
@@ -111,6 +119,21 @@ impl fmt::Display for Error {
             Self::Overflow => write!(f, "slice would exceed maximum size of possible buffers"),
             Self::Conversion(e) => e.fmt(f),
             Self::WrongDataset => write!(f, "This identifier does not belong in this dataset"),
+            Self::RankMismatch(requested, actual) => write!(
+                f,
+                "requested an array of rank {}, but the variable has rank {}",
+                requested, actual
+            ),
+            Self::OutOfRange(pos) => write!(
+                f,
+                "{} value(s) can not be represented in the target type",
+                pos.len()
+            ),
+            Self::MissingFilter(id) => write!(
+                f,
+                "filter {} is needed to read the variable, but is not available",
+                id
+            ),
         }
     }
 }
@@ -3,7 +3,7 @@
 #![allow(clippy::similar_names)]
 use super::error;
 use super::group::Group;
-use super::LOCK;
+use super::{ChunkCache, LOCK};
 use netcdf_sys::*;
 use std::cell::UnsafeCell;
 use std::convert::TryInto;
@@ -60,30 +60,48 @@ impl File {
     /// a generic `Path` object, and ensure read-only on
     /// the `File`
     pub fn open(path: &path::Path) -> error::Result<Self> {
-        let f = CString::new(path.to_str().unwrap()).unwrap();
-        let mut ncid: nc_type = -1;
-        unsafe {
-            let _g = LOCK.lock().unwrap();
-            error::checked(nc_open(f.as_ptr(), NC_NOWRITE, &mut ncid))?;
-        }
-
-        let root = parse_file(ncid)?;
-
-        Ok(Self {
-            ncid,
-            name: path.file_name().unwrap().to_string_lossy().to_string(),
-            root,
-        })
+        Self::open_mode(path, NC_NOWRITE, None)
+    }
+    #[allow(clippy::doc_markdown)]
+    /// Open a netCDF file in read only mode, using `cache`
+    /// as the default chunk cache of its variables
+    pub fn open_with_chunk_cache(path: &path::Path, cache: ChunkCache) -> error::Result<Self> {
+        Self::open_mode(path, NC_NOWRITE, Some(cache))
     }
     #[allow(clippy::doc_markdown)]
     /// Open a netCDF file in append mode (read/write).
     /// The file must already exist.
     pub fn append(path: &path::Path) -> error::Result<Self> {
+        Self::open_mode(path, NC_WRITE, None)
+    }
+    #[allow(clippy::doc_markdown)]
+    /// Open a netCDF file in append mode (read/write), using
+    /// `cache` as the default chunk cache of its variables
+    pub fn append_with_chunk_cache(path: &path::Path, cache: ChunkCache) -> error::Result<Self> {
+        Self::open_mode(path, NC_WRITE, Some(cache))
+    }
+
+    fn open_mode(
+        path: &path::Path,
+        mode: nc_type,
+        cache: Option<ChunkCache>,
+    ) -> error::Result<Self> {
         let f = CString::new(path.to_str().unwrap()).unwrap();
         let mut ncid: nc_type = -1;
-        unsafe {
+        {
             let _g = LOCK.lock().unwrap();
-            error::checked(nc_open(f.as_ptr(), NC_WRITE, &mut ncid))?;
+            match cache {
+                None => unsafe { error::checked(nc_open(f.as_ptr(), mode, &mut ncid))? },
+                Some(cache) => {
+                    // The global chunk cache applies to the variables of files
+                    // opened afterwards, so it is changed only for this file
+                    let previous = crate::global_chunk_cache()?;
+                    crate::set_global_chunk_cache(cache)?;
+                    let opened = unsafe { error::checked(nc_open(f.as_ptr(), mode, &mut ncid)) };
+                    crate::set_global_chunk_cache(previous)?;
+                    opened?;
+                }
+            }
         }
 
         let root = parse_file(ncid)?;
@@ -158,6 +176,15 @@ impl ReadOnlyFile {
             file: File::open(path)?,
         })
     }
+
+    pub(crate) fn open_with_chunk_cache(
+        path: &path::Path,
+        cache: ChunkCache,
+    ) -> error::Result<Self> {
+        Ok(Self {
+            file: File::open_with_chunk_cache(path, cache)?,
+        })
+    }
 }
 
 #[cfg(feature = "memory")]
@@ -390,6 +417,7 @@ fn get_variables(ncid: nc_type, g: &Group) -> error::Result<Vec<Variable>> {
             dimensions,
             name,
             vartype,
+            conversion: Default::default(),
         };
 
         variables.push(v);
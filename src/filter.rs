@@ -0,0 +1,280 @@
+//! Generic HDF5 filters, which are applied to the chunks of a variable
+//! when writing, and reversed when reading. Filters other than the
+//! built-in ones (deflate, shuffle, fletcher32 and szip) are provided by
+//! plugins, which are found through `HDF5_PLUGIN_PATH`
+
+use super::error;
+use super::variable::Variable;
+use super::LOCK;
+use netcdf_sys::*;
+
+/// Compressor used by the blosc filter
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BloscCompressor {
+    /// BloscLZ
+    BloscLz,
+    /// LZ4
+    Lz4,
+    /// LZ4 with high compression
+    Lz4hc,
+    /// Snappy
+    Snappy,
+    /// Zlib
+    Zlib,
+    /// Zstandard
+    Zstd,
+}
+
+impl BloscCompressor {
+    fn code(self) -> u32 {
+        match self {
+            Self::BloscLz => 0,
+            Self::Lz4 => 1,
+            Self::Lz4hc => 2,
+            Self::Snappy => 3,
+            Self::Zlib => 4,
+            Self::Zstd => 5,
+        }
+    }
+
+    fn from_code(code: u32) -> Option<Self> {
+        Some(match code {
+            0 => Self::BloscLz,
+            1 => Self::Lz4,
+            2 => Self::Lz4hc,
+            3 => Self::Snappy,
+            4 => Self::Zlib,
+            5 => Self::Zstd,
+            _ => return None,
+        })
+    }
+}
+
+/// A filter given by its HDF5 filter id and parameters,
+/// with typed variants for the common filters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// Deflate (zlib) with `level` in 0..=9
+    Deflate {
+        /// Compression level
+        level: u32,
+    },
+    /// Byte shuffling
+    Shuffle,
+    /// Fletcher32 checksums
+    Fletcher32,
+    /// Szip, which is often only available for reading
+    Szip {
+        /// Options, such as `NC_SZIP_NN` (32) or `NC_SZIP_EC` (4)
+        options_mask: u32,
+        /// Number of pixels per block
+        pixels_per_block: u32,
+    },
+    /// Bzip2 with `level` in 1..=9
+    Bzip2 {
+        /// Compression level
+        level: u32,
+    },
+    /// Zstandard with `level` in -131072..=22
+    Zstd {
+        /// Compression level
+        level: i32,
+    },
+    /// Blosc, using one of several compressors
+    Blosc {
+        /// The compressor used inside blosc
+        compressor: BloscCompressor,
+        /// Compression level in 0..=9
+        level: u32,
+        /// Shuffling done by blosc, 0 for none,
+        /// 1 for byte and 2 for bit shuffling
+        shuffle: u32,
+    },
+    /// Any other filter
+    Other {
+        /// HDF5 filter id
+        id: u32,
+        /// Parameters of the filter
+        params: Vec<u32>,
+    },
+}
+
+impl Filter {
+    /// HDF5 id of the filter
+    pub fn id(&self) -> u32 {
+        match self {
+            Self::Deflate { .. } => H5Z_FILTER_DEFLATE,
+            Self::Shuffle => H5Z_FILTER_SHUFFLE,
+            Self::Fletcher32 => H5Z_FILTER_FLETCHER32,
+            Self::Szip { .. } => H5Z_FILTER_SZIP,
+            Self::Bzip2 { .. } => H5Z_FILTER_BZIP2,
+            Self::Zstd { .. } => H5Z_FILTER_ZSTD,
+            Self::Blosc { .. } => H5Z_FILTER_BLOSC,
+            Self::Other { id, .. } => *id,
+        }
+    }
+
+    /// Parameters of the filter, as passed to `nc_def_var_filter`
+    #[allow(clippy::cast_sign_loss)]
+    pub fn params(&self) -> Vec<u32> {
+        match self {
+            Self::Deflate { level } | Self::Bzip2 { level } => vec![*level],
+            Self::Shuffle | Self::Fletcher32 => vec![],
+            Self::Szip {
+                options_mask,
+                pixels_per_block,
+            } => vec![*options_mask, *pixels_per_block],
+            Self::Zstd { level } => vec![*level as u32],
+            // The first four parameters are filled in by the plugin
+            Self::Blosc {
+                compressor,
+                level,
+                shuffle,
+            } => vec![0, 0, 0, 0, *level, *shuffle, compressor.code()],
+            Self::Other { params, .. } => params.clone(),
+        }
+    }
+
+    /// Interpret a filter id and parameters, as stored in a file. The
+    /// parameters may include values added by the filter when writing
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn from_id_params(id: u32, params: &[u32]) -> Self {
+        let other = || Self::Other {
+            id,
+            params: params.to_vec(),
+        };
+        match (id, params) {
+            (H5Z_FILTER_DEFLATE, [level, ..]) => Self::Deflate { level: *level },
+            (H5Z_FILTER_SHUFFLE, _) => Self::Shuffle,
+            (H5Z_FILTER_FLETCHER32, _) => Self::Fletcher32,
+            (H5Z_FILTER_SZIP, [options_mask, pixels_per_block, ..]) => Self::Szip {
+                options_mask: *options_mask,
+                pixels_per_block: *pixels_per_block,
+            },
+            (H5Z_FILTER_BZIP2, [level, ..]) => Self::Bzip2 { level: *level },
+            (H5Z_FILTER_ZSTD, [level, ..]) => Self::Zstd {
+                level: *level as i32,
+            },
+            (H5Z_FILTER_BLOSC, [_, _, _, _, level, shuffle, code, ..]) => {
+                match BloscCompressor::from_code(*code) {
+                    Some(compressor) => Self::Blosc {
+                        compressor,
+                        level: *level,
+                        shuffle: *shuffle,
+                    },
+                    None => other(),
+                }
+            }
+            _ => other(),
+        }
+    }
+}
+
+impl Variable {
+    /// Adds a filter to the variable, which is applied after any filters
+    /// added earlier. Must be set before filling in data, and requires
+    /// the variable to be chunked
+    pub fn add_filter(&mut self, filter: &Filter) -> error::Result<()> {
+        let params = filter.params();
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_def_var_filter(
+                self.ncid,
+                self.varid,
+                filter.id(),
+                params.len(),
+                params.as_ptr(),
+            ))
+        }
+    }
+
+    /// Get the filters of the variable, in the order they are applied
+    /// when writing
+    pub fn filter_values(&self) -> error::Result<Vec<Filter>> {
+        let _l = LOCK.lock().unwrap();
+        let mut nfilters = 0;
+        unsafe {
+            error::checked(nc_inq_var_filter_ids(
+                self.ncid,
+                self.varid,
+                &mut nfilters,
+                std::ptr::null_mut(),
+            ))?;
+        }
+        let mut ids = vec![0; nfilters];
+        if nfilters > 0 {
+            unsafe {
+                error::checked(nc_inq_var_filter_ids(
+                    self.ncid,
+                    self.varid,
+                    &mut nfilters,
+                    ids.as_mut_ptr(),
+                ))?;
+            }
+        }
+
+        ids.into_iter()
+            .map(|id| {
+                let mut nparams = 0;
+                unsafe {
+                    error::checked(nc_inq_var_filter_info(
+                        self.ncid,
+                        self.varid,
+                        id,
+                        &mut nparams,
+                        std::ptr::null_mut(),
+                    ))?;
+                }
+                let mut params = vec![0; nparams];
+                if nparams > 0 {
+                    unsafe {
+                        error::checked(nc_inq_var_filter_info(
+                            self.ncid,
+                            self.varid,
+                            id,
+                            &mut nparams,
+                            params.as_mut_ptr(),
+                        ))?;
+                    }
+                }
+                Ok(Filter::from_id_params(id, &params))
+            })
+            .collect()
+    }
+
+    /// Whether `filter` is available for reading and writing this
+    /// variable, i.e. either built-in or provided by a plugin
+    pub fn filter_available(&self, filter: &Filter) -> error::Result<bool> {
+        let _l = LOCK.lock().unwrap();
+        match unsafe { nc_inq_filter_avail(self.ncid, filter.id()) } {
+            NC_NOERR => Ok(true),
+            NC_ENOFILTER => Ok(false),
+            e => Err(e.into()),
+        }
+    }
+
+    /// Get the filters of the variable which are not available, and
+    /// would make reading the variable fail. Useful for reporting which
+    /// plugins are missing
+    pub fn missing_filters(&self) -> error::Result<Vec<Filter>> {
+        let mut missing = Vec::new();
+        for filter in self.filter_values()? {
+            if !self.filter_available(&filter)? {
+                missing.push(filter);
+            }
+        }
+        Ok(missing)
+    }
+
+    /// The error for a read which failed with `NC_ENOFILTER`,
+    /// naming the first filter of the variable which is missing
+    pub(crate) fn missing_filter(&self) -> error::Error {
+        match self.missing_filters() {
+            Ok(missing) => match missing.first() {
+                Some(filter) => error::Error::MissingFilter(filter.id()),
+                None => error::Error::Netcdf(NC_ENOFILTER),
+            },
+            Err(e) => e,
+        }
+    }
+}
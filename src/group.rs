@@ -155,7 +155,7 @@ impl Group {
 
     /// Asserts all dimensions exists, and gets a copy of these
     /// (will be moved into a Variable)
-    fn find_dimensions(&self, dims: &[&str]) -> error::Result<Vec<Dimension>> {
+    pub(crate) fn find_dimensions(&self, dims: &[&str]) -> error::Result<Vec<Dimension>> {
         let (d, e): (Vec<_>, Vec<_>) = dims
             .iter()
             .map(|name| {
@@ -266,6 +266,202 @@ impl Group {
         self.variables.push(var);
         Ok(self.variable_mut(name).unwrap())
     }
+
+    /// Adds a variable of type `NC_CHAR` for storing strings of
+    /// at most `max_len` bytes, using the classic convention of
+    /// a trailing string length dimension.
+    ///
+    /// The string length dimension is named `string<max_len>`, and
+    /// is created in this group unless it exists already
+    pub fn add_char_string_variable(
+        &mut self,
+        name: &str,
+        dims: &[&str],
+        max_len: usize,
+    ) -> error::Result<&mut Variable> {
+        if self.variable(name).is_some() {
+            return Err(error::Error::AlreadyExists("variable".into()));
+        }
+        if max_len == 0 {
+            return Err(error::Error::ZeroSlice);
+        }
+
+        let strlen_name = format!("string{}", max_len);
+        let existing = self
+            .dimension(&strlen_name)
+            .or_else(|| self.parents().find_map(|p| p.dimension(&strlen_name)));
+        match existing {
+            Some(d) if d.len() != max_len || d.is_unlimited() => {
+                return Err(error::Error::AlreadyExists(format!(
+                    "dimension {}",
+                    strlen_name
+                )));
+            }
+            Some(_) => {}
+            None => {
+                self.add_dimension(&strlen_name, max_len)?;
+            }
+        }
+
+        let mut dims = dims.to_vec();
+        dims.push(&strlen_name);
+        let d = self.find_dimensions(&dims)?;
+        let var = Variable::new(self.grpid.unwrap_or(self.ncid), name, d, NC_CHAR)?;
+
+        self.variables.push(var);
+        Ok(self.variable_mut(name).unwrap())
+    }
+}
+
+/// Appends records along an unlimited dimension to several variables,
+/// created by [`Group::record_writer`].
+///
+/// A record is not written atomically. The values are checked before
+/// anything is written, but `libnetcdf` can not undo a write, so a
+/// record may be left partly written (see [`RecordWriter::write`])
+///
+/// ```no_run
+/// # fn main() -> Result<(), netcdf::error::Error> {
+/// let mut file = netcdf::append("observations.nc")?;
+/// let mut writer = file.record_writer("time")?;
+/// writer
+///     .put("time", &[3600.0f64])?
+///     .put("temperature", &[280.1f32, 281.5, 279.9])?;
+/// writer.write()?;
+/// # Ok(()) }
+/// ```
+pub struct RecordWriter<'g> {
+    group: &'g mut Group,
+    dimension: Dimension,
+    index: usize,
+    pending: Vec<PendingPut>,
+}
+
+/// Values of a variable waiting to be written
+struct PendingPut {
+    variable: String,
+    indices: Vec<usize>,
+    slice_len: Vec<usize>,
+    put: PutValues,
+}
+
+type PutValues = Box<dyn Fn(&mut Variable, &[usize], &[usize]) -> error::Result<()>>;
+
+impl std::fmt::Debug for RecordWriter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("RecordWriter")
+            .field("dimension", &self.dimension.name())
+            .field("index", &self.index)
+            .field(
+                "pending",
+                &self.pending.iter().map(|p| &p.variable).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Group {
+    /// Get a writer appending records along the unlimited dimension
+    /// `dimension`, starting after the current last record
+    pub fn record_writer(&mut self, dimension: &str) -> error::Result<RecordWriter<'_>> {
+        let dimension = self.find_dimensions(&[dimension])?.remove(0);
+        if !dimension.is_unlimited() {
+            return Err(format!("dimension {} is not unlimited", dimension.name()).into());
+        }
+        Ok(RecordWriter {
+            index: dimension.len(),
+            group: self,
+            dimension,
+            pending: Vec::new(),
+        })
+    }
+}
+
+impl<'g> RecordWriter<'g> {
+    /// Index of the record being written
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Adds the values of `variable` for the current record, which
+    /// are written by [`RecordWriter::write`]. The values must fill
+    /// the other dimensions of the variable, using their current lengths
+    pub fn put<T: Numeric + Clone + 'static>(
+        &mut self,
+        variable: &str,
+        values: &[T],
+    ) -> error::Result<&mut Self> {
+        let var = self
+            .group
+            .variable(variable)
+            .ok_or_else(|| error::Error::NotFound(format!("variable {}", variable)))?;
+        let position = var
+            .dimensions()
+            .iter()
+            .position(|d| d.ncid == self.dimension.ncid && d.id == self.dimension.id)
+            .ok_or_else(|| {
+                error::Error::from(format!(
+                    "variable {} is not along dimension {}",
+                    variable,
+                    self.dimension.name()
+                ))
+            })?;
+        if self.pending.iter().any(|p| p.variable == variable) {
+            return Err(error::Error::AlreadyExists(format!(
+                "values for variable {}",
+                variable
+            )));
+        }
+
+        let mut indices = vec![0; var.dimensions().len()];
+        indices[position] = self.index;
+        let mut slice_len = var
+            .dimensions()
+            .iter()
+            .map(Dimension::len)
+            .collect::<Vec<_>>();
+        slice_len[position] = 1;
+        let len = slice_len.iter().product::<usize>();
+        if values.len() != len {
+            return Err(error::Error::BufferLen(values.len(), len));
+        }
+
+        var.check_conversion::<T>()?;
+        var.check_range_put(values.iter().enumerate())?;
+
+        let values = values.to_vec();
+        self.pending.push(PendingPut {
+            variable: variable.to_string(),
+            indices,
+            slice_len,
+            put: Box::new(move |var, indices, slice_len| {
+                var.put_values(&values, Some(indices), Some(slice_len))
+            }),
+        });
+        Ok(self)
+    }
+
+    /// Writes the values given by [`RecordWriter::put`], and advances
+    /// to the next record. All values are checked before anything is
+    /// written, but the netCDF library may still fail to write a
+    /// variable. The record is then only partly written: the variables
+    /// before it are kept, and the rest stay pending, so that calling
+    /// `write` again retries them for the same record
+    pub fn write(&mut self) -> error::Result<()> {
+        if self.pending.is_empty() {
+            return Err("no values given for the record".into());
+        }
+        while let Some(pending) = self.pending.first() {
+            let var = self
+                .group
+                .variable_mut(&pending.variable)
+                .ok_or_else(|| error::Error::NotFound(format!("variable {}", pending.variable)))?;
+            (pending.put)(var, &pending.indices, &pending.slice_len)?;
+            self.pending.remove(0);
+        }
+        self.index += 1;
+        Ok(())
+    }
 }
 
 struct ParentIterator<'a> {
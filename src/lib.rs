@@ -69,15 +69,24 @@ use netcdf_sys::nc_type;
 use std::sync::Mutex;
 
 pub mod attribute;
+pub mod cf;
+#[cfg(feature = "num-complex")]
+pub mod complex;
 pub mod dimension;
 pub mod error;
 pub mod file;
+#[cfg(feature = "filters")]
+pub mod filter;
 pub mod group;
 pub mod variable;
 
 pub use attribute::*;
+#[cfg(feature = "num-complex")]
+pub use complex::*;
 pub use dimension::*;
 pub use file::*;
+#[cfg(feature = "filters")]
+pub use filter::*;
 pub use group::*;
 pub use variable::*;
 
@@ -113,6 +122,103 @@ pub fn open_mem<'a>(name: Option<&str>, mem: &'a [u8]) -> error::Result<MemFile<
     file::MemFile::new(name, mem)
 }
 
+/// Open a netcdf file in append mode, using `cache`
+/// as the default chunk cache of its variables
+pub fn append_with_chunk_cache<P>(name: P, cache: ChunkCache) -> error::Result<File>
+where
+    P: AsRef<std::path::Path>,
+{
+    File::append_with_chunk_cache(name.as_ref(), cache)
+}
+
+/// Open a netcdf file in read mode, using `cache`
+/// as the default chunk cache of its variables
+pub fn open_with_chunk_cache<P>(name: P, cache: ChunkCache) -> error::Result<ReadOnlyFile>
+where
+    P: AsRef<std::path::Path>,
+{
+    ReadOnlyFile::open_with_chunk_cache(name.as_ref(), cache)
+}
+
+/// Settings of the cache holding decompressed chunks of a variable
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChunkCache {
+    /// Size of the cache in bytes
+    pub size: usize,
+    /// Number of slots in the hash table of the cache, which should be
+    /// a prime number larger than the number of chunks in the cache
+    pub slots: usize,
+    /// Preemption of fully read or written chunks in 0.0..=1.0,
+    /// where 1.0 always evicts such chunks first
+    pub preemption: f32,
+}
+
+/// Get the chunk cache used for variables of files opened or created later
+pub fn chunk_cache() -> error::Result<ChunkCache> {
+    let _l = LOCK.lock().unwrap();
+    global_chunk_cache()
+}
+
+/// Set the chunk cache used for variables of files opened or created later
+pub fn set_chunk_cache(cache: ChunkCache) -> error::Result<()> {
+    let _l = LOCK.lock().unwrap();
+    set_global_chunk_cache(cache)
+}
+
+/// Must be called while holding the lock
+pub(crate) fn global_chunk_cache() -> error::Result<ChunkCache> {
+    let mut cache = ChunkCache {
+        size: 0,
+        slots: 0,
+        preemption: 0.0,
+    };
+    unsafe {
+        error::checked(netcdf_sys::nc_get_chunk_cache(
+            &mut cache.size,
+            &mut cache.slots,
+            &mut cache.preemption,
+        ))?;
+    }
+    Ok(cache)
+}
+
+/// Must be called while holding the lock
+pub(crate) fn set_global_chunk_cache(cache: ChunkCache) -> error::Result<()> {
+    unsafe {
+        error::checked(netcdf_sys::nc_set_chunk_cache(
+            cache.size,
+            cache.slots,
+            cache.preemption,
+        ))
+    }
+}
+
+/// Version of the linked netCDF library as `(major, minor, patch)`,
+/// or `None` if the version string can not be parsed
+pub fn library_version() -> Option<(u32, u32, u32)> {
+    let version = unsafe { std::ffi::CStr::from_ptr(netcdf_sys::nc_inq_libvers()) };
+    parse_version(&version.to_string_lossy())
+}
+
+/// Parses the leading `major.minor.patch` of a version string,
+/// such as "4.9.2 of Mar 14 2023 12:00:00 $"
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let version = version.split_whitespace().next()?;
+    let mut parts = version.split(|c: char| !c.is_ascii_digit()).map(str::parse);
+    Some((
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next().and_then(Result::ok).unwrap_or(0),
+    ))
+}
+
+#[test]
+fn version_parsing() {
+    assert_eq!(parse_version("4.9.2 of Mar 14 2023 $"), Some((4, 9, 2)));
+    assert_eq!(parse_version("4.10-development"), Some((4, 10, 0)));
+    assert_eq!(parse_version("unknown"), None);
+}
+
 lazy_static! {
     /// Use this when accessing netcdf functions
     pub(crate) static ref LOCK: Mutex<()> = Mutex::new(());
@@ -5,17 +5,19 @@ use super::attribute::AttrValue;
 use super::attribute::Attribute;
 use super::dimension::Dimension;
 use super::error;
-use super::LOCK;
+use super::{ChunkCache, LOCK};
 #[cfg(feature = "ndarray")]
 use ndarray::ArrayD;
 use netcdf_sys::*;
+use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::ffi::CStr;
 use std::marker::Sized;
 
 #[allow(clippy::doc_markdown)]
 /// This struct defines a netCDF variable.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Variable {
     /// The variable name
     pub(crate) name: String,
@@ -24,6 +26,27 @@ pub struct Variable {
     pub(crate) vartype: nc_type,
     pub(crate) ncid: nc_type,
     pub(crate) varid: nc_type,
+    /// How to convert between the memory type and `vartype`
+    pub(crate) conversion: Conversion,
+}
+
+/// Policy for converting between the type used in memory and
+/// the type of the variable in the file
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Conversion {
+    /// The type in memory must be the same as the type
+    /// of the variable, otherwise `Error::TypeMismatch` is returned
+    Strict,
+    /// Let `libnetcdf` convert between the types. Values which
+    /// can not be represented in the target type gives the opaque
+    /// error `Error::Netcdf(NC_ERANGE)`
+    #[default]
+    Lossy,
+    /// Let `libnetcdf` convert between the types, but report which
+    /// elements can not be represented in the target type through
+    /// `Error::OutOfRange`. Values are checked before writing,
+    /// and nothing is written if any value is out of range
+    Checked,
 }
 
 /// Enum for variables endianness
@@ -37,6 +60,329 @@ pub enum Endianness {
     Big,
 }
 
+/// How the data of a variable is stored in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Storage {
+    /// A single block, which can not be compressed
+    /// and does not allow unlimited dimensions
+    Contiguous,
+    /// Inside the metadata of the variable, for
+    /// variables smaller than 64 KiB
+    Compact,
+    /// Chunks with the given lengths
+    Chunked(Vec<usize>),
+}
+
+/// Strategies for choosing the chunk lengths of a variable,
+/// see [`Variable::auto_chunk`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChunkStrategy {
+    /// Reading long series along the first dimension (usually time) at
+    /// few points. Chunks span as much of the first dimension as fits
+    /// in [`DEFAULT_CHUNK_BYTES`], with small extents in the others
+    TimeSeries,
+    /// Reading whole maps (the last two dimensions) at single indices
+    /// of the others. Chunks have length 1 along the leading dimensions
+    Maps,
+    /// Chunks of about `target_bytes`, with the same length along
+    /// every dimension where the dimension lengths allow it
+    Balanced {
+        /// Target size of a chunk in bytes
+        target_bytes: usize,
+    },
+}
+
+/// Target size of a chunk for [`ChunkStrategy::TimeSeries`] and
+/// [`ChunkStrategy::Maps`], which fits in the default HDF5 chunk cache
+pub const DEFAULT_CHUNK_BYTES: usize = 1 << 20;
+
+/// Length assumed for unlimited dimensions when choosing chunk lengths,
+/// unless they are longer already
+const UNLIMITED_CHUNK_LEN: usize = 1024;
+
+/// Chunk lengths for dimensions of length `lens`, where
+/// each element takes `elem_size` bytes
+fn chunk_lengths(strategy: ChunkStrategy, lens: &[usize], elem_size: usize) -> Vec<usize> {
+    let target_bytes = match strategy {
+        ChunkStrategy::Balanced { target_bytes } => target_bytes,
+        _ => DEFAULT_CHUNK_BYTES,
+    };
+    let target = (target_bytes / elem_size.max(1)).max(1);
+    let lens = lens.iter().map(|&l| l.max(1)).collect::<Vec<_>>();
+
+    match strategy {
+        ChunkStrategy::Balanced { .. } => balanced_chunk(&lens, target),
+        ChunkStrategy::Maps => {
+            let k = lens.len().saturating_sub(2);
+            let mut chunk = vec![1; k];
+            chunk.extend(balanced_chunk(&lens[k..], target));
+            chunk
+        }
+        ChunkStrategy::TimeSeries => match lens.split_first() {
+            None => Vec::new(),
+            Some((&first, rest)) => {
+                let first = first.min(target);
+                let mut chunk = vec![first];
+                chunk.extend(balanced_chunk(rest, target / first));
+                chunk
+            }
+        },
+    }
+}
+
+/// Chunk lengths of about `target` elements, as close to a cube
+/// as `lens` allows. Dimensions shorter than the side of the cube
+/// are not split, leaving more of the target for the others
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn balanced_chunk(lens: &[usize], target: usize) -> Vec<usize> {
+    let mut chunk = lens.to_vec();
+    let mut free = (0..lens.len()).collect::<Vec<_>>();
+    let mut target = target.max(1) as f64;
+    while !free.is_empty() {
+        let side = target.powf(1.0 / free.len() as f64);
+        let (short, long): (Vec<usize>, Vec<usize>) =
+            free.iter().partition(|&&i| lens[i] as f64 <= side);
+        if short.is_empty() {
+            for i in long {
+                // Allow for rounding errors in the root
+                chunk[i] = ((side + 1e-6).floor() as usize).max(1).min(lens[i]);
+            }
+            break;
+        }
+        for &i in &short {
+            target /= lens[i] as f64;
+        }
+        free = long;
+    }
+    chunk
+}
+
+#[test]
+fn chunk_shapes() {
+    let lens = [8760, 100, 100];
+    assert_eq!(
+        chunk_lengths(ChunkStrategy::TimeSeries, &lens, 4),
+        [8760, 5, 5]
+    );
+    assert_eq!(chunk_lengths(ChunkStrategy::Maps, &lens, 4), [1, 100, 100]);
+    assert_eq!(
+        chunk_lengths(ChunkStrategy::Balanced { target_bytes: 4000 }, &lens, 4),
+        [10, 10, 10]
+    );
+    // Short dimensions are not split
+    assert_eq!(
+        chunk_lengths(
+            ChunkStrategy::Balanced { target_bytes: 8000 },
+            &[2, 1000],
+            1
+        ),
+        [2, 1000]
+    );
+    assert_eq!(
+        chunk_lengths(ChunkStrategy::Maps, &[10, 4000, 4000], 8),
+        [1, 362, 362]
+    );
+    assert_eq!(chunk_lengths(ChunkStrategy::TimeSeries, &[0], 8), [1]);
+}
+
+#[cfg(feature = "ndarray")]
+/// Iterator over blocks of a variable, created by [`Variable::chunks`]
+#[derive(Debug)]
+pub struct Chunks<'a, T> {
+    variable: &'a Variable,
+    block: Vec<usize>,
+    lens: Vec<usize>,
+    next: Option<Vec<usize>>,
+    _type: std::marker::PhantomData<T>,
+}
+
+#[cfg(feature = "ndarray")]
+impl<'a, T: Numeric> Iterator for Chunks<'a, T> {
+    /// Indices of the first element of the block, and the values
+    type Item = error::Result<(Vec<usize>, ArrayD<T>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.next.take()?;
+        let slice_len = indices
+            .iter()
+            .zip(&self.block)
+            .zip(&self.lens)
+            .map(|((&i, &b), &l)| b.min(l - i))
+            .collect::<Vec<_>>();
+
+        // Advance with the last dimension varying the fastest
+        let mut next = indices.clone();
+        for d in (0..next.len()).rev() {
+            next[d] += self.block[d];
+            if next[d] < self.lens[d] {
+                self.next = Some(next);
+                break;
+            }
+            next[d] = 0;
+        }
+
+        Some(
+            self.variable
+                .values(Some(&indices), Some(&slice_len))
+                .map(|values| (indices, values)),
+        )
+    }
+}
+
+#[cfg(feature = "ndarray")]
+/// Iterator over the records of a variable, created by [`Variable::records`]
+#[derive(Debug)]
+pub struct Records<'a, T> {
+    variable: &'a Variable,
+    dimension: usize,
+    lens: Vec<usize>,
+    next: usize,
+    _type: std::marker::PhantomData<T>,
+}
+
+#[cfg(feature = "ndarray")]
+impl<'a, T: Numeric> Iterator for Records<'a, T> {
+    /// Values of the record, without the record dimension
+    type Item = error::Result<ArrayD<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.lens[self.dimension] {
+            return None;
+        }
+        let mut indices = vec![0; self.lens.len()];
+        indices[self.dimension] = self.next;
+        let mut slice_len = self.lens.clone();
+        slice_len[self.dimension] = 1;
+        self.next += 1;
+
+        Some(
+            self.variable
+                .values(Some(&indices), Some(&slice_len))
+                .map(|values| values.index_axis_move(ndarray::Axis(self.dimension), 0)),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.lens[self.dimension].saturating_sub(self.next);
+        (n, Some(n))
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<'a, T: Numeric> ExactSizeIterator for Records<'a, T> {}
+
+/// Settings of the szip compression of a variable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Szip {
+    /// Options, such as `NC_SZIP_NN` (32) or `NC_SZIP_EC` (4)
+    pub options_mask: nc_type,
+    /// Number of pixels per block
+    pub pixels_per_block: nc_type,
+}
+
+/// Shuffle, deflate and checksum filters of a variable,
+/// created by [`Variable::filters`]. All filters are off
+/// unless enabled, and nothing is set before calling [`Filters::set`]
+///
+/// ```no_run
+/// # fn main() -> Result<(), netcdf::error::Error> {
+/// let mut file = netcdf::create("filters.nc")?;
+/// file.add_dimension("x", 100)?;
+/// let var = file.add_variable::<f32>("temperature", &["x"])?;
+/// var.filters().shuffle(true).deflate(4).fletcher32(true).set()?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct Filters<'a> {
+    variable: &'a mut Variable,
+    shuffle: bool,
+    deflate_level: Option<nc_type>,
+    fletcher32: bool,
+}
+
+impl<'a> Filters<'a> {
+    /// Reorder the bytes of the values before compressing, which
+    /// often improves the compression of floating point data
+    pub fn shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// Compress using deflate, with `deflate_level` in 0..=9, see
+    /// [`Variable::compression`]
+    pub fn deflate(mut self, deflate_level: nc_type) -> Self {
+        self.deflate_level = Some(deflate_level);
+        self
+    }
+
+    /// Store Fletcher32 checksums, which are verified when reading
+    pub fn fletcher32(mut self, fletcher32: bool) -> Self {
+        self.fletcher32 = fletcher32;
+        self
+    }
+
+    /// Sets the filters on the variable
+    pub fn set(self) -> error::Result<()> {
+        let _l = LOCK.lock().unwrap();
+        let variable = self.variable;
+        unsafe {
+            if self.shuffle || self.deflate_level.is_some() {
+                error::checked(nc_def_var_deflate(
+                    variable.ncid,
+                    variable.varid,
+                    self.shuffle as _,
+                    self.deflate_level.is_some() as _,
+                    self.deflate_level.unwrap_or(0),
+                ))?;
+            }
+            if self.fletcher32 {
+                error::checked(nc_def_var_fletcher32(
+                    variable.ncid,
+                    variable.varid,
+                    true as _,
+                ))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "quantize")]
+/// Quantization is only available when the linked library is at least
+/// netCDF 4.9.0, which may be older than the headers used when building
+fn check_quantize_version() -> error::Result<()> {
+    match crate::library_version() {
+        Some(version) if version >= (4, 9, 0) => Ok(()),
+        Some((major, minor, patch)) => Err(format!(
+            "quantization requires netCDF 4.9.0, but the linked library is {}.{}.{}",
+            major, minor, patch
+        )
+        .into()),
+        None => Err(
+            "quantization requires netCDF 4.9.0, but the version of the linked library is unknown"
+                .into(),
+        ),
+    }
+}
+
+#[cfg(feature = "quantize")]
+/// Algorithms for quantizing floating point values, which zero the bits
+/// not needed for a number of significant digits (or bits) so the values
+/// compress better. Requires netCDF 4.9.0
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Quantize {
+    /// BitGroom, alternately rounding up and down, using decimal digits
+    BitGroom,
+    /// Granular BitRound, using decimal digits
+    GranularBitRound,
+    /// BitRound, using binary digits
+    BitRound,
+}
+
 #[allow(clippy::len_without_is_empty)]
 impl Variable {
     /// Get name of variable
@@ -67,6 +413,18 @@ impl Variable {
     pub fn vartype(&self) -> nc_type {
         self.vartype
     }
+    /// Get the conversion policy used when reading and writing values
+    pub fn conversion(&self) -> Conversion {
+        self.conversion
+    }
+    /// Set the conversion policy used when reading and writing values.
+    ///
+    /// This only applies to this handle of the variable, and is
+    /// not stored in the file. Variables of read-only files can
+    /// be cloned to get a handle with a different policy
+    pub fn set_conversion(&mut self, conversion: Conversion) {
+        self.conversion = conversion
+    }
     /// Get current length of the variable
     pub fn len(&self) -> usize {
         self.dimensions.iter().map(Dimension::len).product()
@@ -105,7 +463,8 @@ impl Variable {
     ///
     /// `deflate_level` can take a value 0..=9, with 0 being no
     /// compression (good for CPU bound tasks), and 9 providing the
-    /// highest compression level (good for memory bound tasks)
+    /// highest compression level (good for memory bound tasks).
+    /// Use [`Variable::filters`] to also enable shuffle or checksums
     pub fn compression(&mut self, deflate_level: nc_type) -> error::Result<()> {
         let _l = LOCK.lock().unwrap();
         unsafe {
@@ -121,6 +480,171 @@ impl Variable {
         Ok(())
     }
 
+    /// Sets shuffle, deflate and checksums on the variable together,
+    /// see [`Filters`]. Must be set before filling in data
+    pub fn filters(&mut self) -> Filters<'_> {
+        Filters {
+            variable: self,
+            shuffle: false,
+            deflate_level: None,
+            fletcher32: false,
+        }
+    }
+
+    /// Get whether the shuffle filter is applied to the variable
+    pub fn shuffle_value(&self) -> error::Result<bool> {
+        Ok(self.deflate_settings()?.0)
+    }
+
+    /// Get the deflate level of the variable,
+    /// or `None` if the variable is not deflated
+    pub fn deflate_value(&self) -> error::Result<Option<nc_type>> {
+        Ok(self.deflate_settings()?.1)
+    }
+
+    fn deflate_settings(&self) -> error::Result<(bool, Option<nc_type>)> {
+        let mut shuffle = 0;
+        let mut deflate = 0;
+        let mut deflate_level = 0;
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_inq_var_deflate(
+                self.ncid,
+                self.varid,
+                &mut shuffle,
+                &mut deflate,
+                &mut deflate_level,
+            ))?;
+        }
+        Ok((
+            shuffle != 0,
+            if deflate == 0 {
+                None
+            } else {
+                Some(deflate_level)
+            },
+        ))
+    }
+
+    /// Get whether Fletcher32 checksums are computed for the variable
+    pub fn fletcher32_value(&self) -> error::Result<bool> {
+        let mut fletcher32 = 0;
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_inq_var_fletcher32(
+                self.ncid,
+                self.varid,
+                &mut fletcher32,
+            ))?;
+        }
+        Ok(fletcher32 != 0)
+    }
+
+    /// Get the szip settings of the variable, or `None` if the
+    /// variable is not compressed using szip. Szip can only be
+    /// read and not set, since the encoder may not be available
+    pub fn szip_value(&self) -> error::Result<Option<Szip>> {
+        let mut options_mask = 0;
+        let mut pixels_per_block = 0;
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_inq_var_szip(
+                self.ncid,
+                self.varid,
+                &mut options_mask,
+                &mut pixels_per_block,
+            ))?;
+        }
+        if options_mask == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Szip {
+            options_mask,
+            pixels_per_block,
+        }))
+    }
+
+    #[cfg(feature = "quantize")]
+    /// Sets quantization on a floating point variable, keeping `nsd`
+    /// significant digits (binary digits for `Quantize::BitRound`).
+    /// Must be set before filling in data. Fails if the linked
+    /// library is older than netCDF 4.9.0.
+    ///
+    /// This is lossy, and is best combined with compression, see
+    /// [`Variable::compression`]
+    pub fn quantize(&mut self, mode: Quantize, nsd: nc_type) -> error::Result<()> {
+        let quantize_mode = match mode {
+            Quantize::BitGroom => NC_QUANTIZE_BITGROOM,
+            Quantize::GranularBitRound => NC_QUANTIZE_GRANULARBR,
+            Quantize::BitRound => NC_QUANTIZE_BITROUND,
+        };
+        check_quantize_version()?;
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_def_var_quantize(
+                self.ncid,
+                self.varid,
+                quantize_mode,
+                nsd,
+            ))
+        }
+    }
+
+    #[cfg(feature = "quantize")]
+    /// Get the quantization of the variable and the number of
+    /// significant digits, or `None` if the variable is not quantized
+    pub fn quantize_value(&self) -> error::Result<Option<(Quantize, nc_type)>> {
+        check_quantize_version()?;
+        let mut mode = 0;
+        let mut nsd = 0;
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_inq_var_quantize(
+                self.ncid, self.varid, &mut mode, &mut nsd,
+            ))?;
+        }
+        let mode = match mode {
+            NC_NOQUANTIZE => return Ok(None),
+            NC_QUANTIZE_BITGROOM => Quantize::BitGroom,
+            NC_QUANTIZE_GRANULARBR => Quantize::GranularBitRound,
+            NC_QUANTIZE_BITROUND => Quantize::BitRound,
+            _ => return Err(NC_EVARMETA.into()),
+        };
+        Ok(Some((mode, nsd)))
+    }
+
+    /// Set chunking for the variable using `strategy`, based on the
+    /// lengths of the dimensions and the size of the type. Unlimited
+    /// dimensions are assumed to grow to at least 1024 elements.
+    /// Must be set before inserting data
+    pub fn auto_chunk(&mut self, strategy: ChunkStrategy) -> error::Result<()> {
+        if self.dimensions.is_empty() {
+            return Err("a scalar variable can not be chunked".into());
+        }
+        let lens = self
+            .dimensions
+            .iter()
+            .map(|d| {
+                if d.is_unlimited() {
+                    d.len().max(UNLIMITED_CHUNK_LEN)
+                } else {
+                    d.len()
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut elem_size = 0;
+        unsafe {
+            let _l = LOCK.lock().unwrap();
+            error::checked(nc_inq_type(
+                self.ncid,
+                self.vartype,
+                std::ptr::null_mut(),
+                &mut elem_size,
+            ))?;
+        }
+        self.chunking(&chunk_lengths(strategy, &lens, elem_size))
+    }
+
     /// Set chunking for variable. Must be set before inserting data
     ///
     /// Use this when reading or writing smaller units of the hypercube than
@@ -150,6 +674,89 @@ impl Variable {
         Ok(())
     }
 
+    /// Get the chunk cache of the variable
+    pub fn chunk_cache(&self) -> error::Result<ChunkCache> {
+        let mut cache = ChunkCache {
+            size: 0,
+            slots: 0,
+            preemption: 0.0,
+        };
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_get_var_chunk_cache(
+                self.ncid,
+                self.varid,
+                &mut cache.size,
+                &mut cache.slots,
+                &mut cache.preemption,
+            ))?;
+        }
+        Ok(cache)
+    }
+
+    /// Set the chunk cache of the variable. A cache holding all chunks
+    /// needed for a read (e.g. all chunks covering a map when reading
+    /// a time series) avoids decompressing the same chunks repeatedly
+    pub fn set_chunk_cache(&self, cache: ChunkCache) -> error::Result<()> {
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_set_var_chunk_cache(
+                self.ncid,
+                self.varid,
+                cache.size,
+                cache.slots,
+                cache.preemption,
+            ))
+        }
+    }
+
+    /// Get the storage layout of the variable
+    pub fn storage(&self) -> error::Result<Storage> {
+        let mut storage = 0;
+        let mut chunksize = vec![0; self.dimensions.len()];
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_inq_var_chunking(
+                self.ncid,
+                self.varid,
+                &mut storage,
+                chunksize.as_mut_ptr(),
+            ))?;
+        }
+        match storage {
+            NC_CONTIGUOUS => Ok(Storage::Contiguous),
+            NC_COMPACT => Ok(Storage::Compact),
+            NC_CHUNKED => Ok(Storage::Chunked(chunksize)),
+            _ => Err(NC_EVARMETA.into()),
+        }
+    }
+
+    /// Store the variable contiguously. Must be set before inserting
+    /// data, and can not be combined with compression or filters
+    pub fn contiguous(&mut self) -> error::Result<()> {
+        self.def_storage(NC_CONTIGUOUS)
+    }
+
+    /// Store the variable in its metadata, which is efficient for small
+    /// variables such as coordinates. The variable must be smaller than
+    /// 64 KiB and have no unlimited dimensions. Must be set before
+    /// inserting data
+    pub fn compact(&mut self) -> error::Result<()> {
+        self.def_storage(NC_COMPACT)
+    }
+
+    fn def_storage(&mut self, storage: nc_type) -> error::Result<()> {
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_def_var_chunking(
+                self.ncid,
+                self.varid,
+                storage,
+                std::ptr::null(),
+            ))
+        }
+    }
+
     /// Checks for array mismatch
     fn check_indices(&self, indices: &[usize], putting: bool) -> error::Result<()> {
         if indices.len() != self.dimensions.len() {
@@ -224,6 +831,59 @@ impl Variable {
         Ok(())
     }
 
+    /// Resolves the offset and extent of a read, where the
+    /// extent defaults to the remainder of every dimension
+    pub(crate) fn read_extents(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<(Vec<usize>, Vec<usize>)> {
+        let indices = if let Some(x) = indices {
+            self.check_indices(x, false)?;
+            x.to_vec()
+        } else {
+            self.default_indices(false)?
+        };
+        let slice_len = if let Some(x) = slice_len {
+            let len = x.iter().fold(1_usize, |acc, &x| acc.saturating_mul(x));
+            self.check_sizelen(len, &indices, x, false)?;
+            x.to_vec()
+        } else {
+            let len = self
+                .dimensions
+                .iter()
+                .zip(&indices)
+                .fold(1_usize, |acc, (d, &i)| {
+                    acc.saturating_mul(d.len().saturating_sub(i))
+                });
+            self.default_sizelen(len, &indices, false)?
+        };
+        Ok((indices, slice_len))
+    }
+
+    /// Finds the extents for putting `nvalues` values, where
+    /// missing `indices` and `slice_len` are given default values
+    pub(crate) fn put_extents(
+        &self,
+        nvalues: usize,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<(Vec<usize>, Vec<usize>)> {
+        let indices = if let Some(x) = indices {
+            self.check_indices(x, true)?;
+            x.to_vec()
+        } else {
+            self.default_indices(true)?
+        };
+        let slice_len = if let Some(x) = slice_len {
+            self.check_sizelen(nvalues, &indices, x, true)?;
+            x.to_vec()
+        } else {
+            self.default_sizelen(nvalues, &indices, true)?
+        };
+        Ok((indices, slice_len))
+    }
+
     /// Assumes indices is valid for this variable
     fn default_sizelen(
         &self,
@@ -276,10 +936,408 @@ impl Variable {
         if wantedlen == usize::max_value() {
             return Err(error::Error::Overflow);
         }
-        if totallen != wantedlen {
-            return Err(error::Error::BufferLen(totallen, wantedlen));
+        if totallen != wantedlen {
+            return Err(error::Error::BufferLen(totallen, wantedlen));
+        }
+        Ok(sizelen)
+    }
+
+    /// Finds the slice lengths for a mapped read/write, and checks
+    /// that `buflen` can hold every element addressed through `map`
+    ///
+    /// Assumes indices is valid for this variable
+    fn mapped_sizelen(
+        &self,
+        buflen: usize,
+        indices: &[usize],
+        slice_len: Option<&[usize]>,
+        map: &[isize],
+        putting: bool,
+    ) -> error::Result<Vec<usize>> {
+        if map.len() != self.dimensions.len() {
+            return Err(error::Error::SliceLen);
+        }
+        let sizelen = if let Some(slice_len) = slice_len {
+            if slice_len.len() != self.dimensions.len() {
+                return Err(error::Error::SliceLen);
+            }
+            for ((&i, &s), d) in indices.iter().zip(slice_len).zip(&self.dimensions) {
+                if s == 0 {
+                    return Err(error::Error::ZeroSlice);
+                }
+                let end = i.checked_add(s).ok_or(error::Error::Overflow)?;
+                if end > d.len() && !(putting && d.is_unlimited()) {
+                    return Err(error::Error::SliceMismatch);
+                }
+            }
+            slice_len.to_vec()
+        } else {
+            indices
+                .iter()
+                .zip(&self.dimensions)
+                .map(|(&i, d)| {
+                    if i >= d.len() {
+                        Err(error::Error::SliceMismatch)
+                    } else {
+                        Ok(d.len() - i)
+                    }
+                })
+                .collect::<error::Result<Vec<_>>>()?
+        };
+
+        // Offset of the last element addressed through the map
+        let mut last = 0_usize;
+        for (&s, &m) in sizelen.iter().zip(map) {
+            if m < 0 {
+                return Err(error::Error::StrideError);
+            }
+            last = (s - 1)
+                .checked_mul(m as usize)
+                .and_then(|x| x.checked_add(last))
+                .ok_or(error::Error::Overflow)?;
+        }
+        let needed = last.checked_add(1).ok_or(error::Error::Overflow)?;
+        if buflen < needed {
+            return Err(error::Error::BufferLen(buflen, needed));
+        }
+
+        Ok(sizelen)
+    }
+
+    /// Finds the extents of a variable of type `NC_CHAR`, with the
+    /// string length dimension (the last dimension) appended to
+    /// `indices` and `slice_len`
+    ///
+    /// `nstrings` must be given when putting
+    fn char_extents(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+        nstrings: Option<usize>,
+    ) -> error::Result<(Vec<usize>, Vec<usize>)> {
+        if self.vartype != NC_CHAR {
+            return Err(error::Error::TypeMismatch);
+        }
+        if self.dimensions.is_empty() {
+            return Err("variable has no string length dimension".into());
+        }
+        self.trailing_extents(indices, slice_len, nstrings)
+    }
+
+    /// Finds the extents of a variable where each element spans the
+    /// whole last dimension, which is appended to `indices` and `slice_len`
+    ///
+    /// `nelems` must be given when putting
+    pub(crate) fn trailing_extents(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+        nelems: Option<usize>,
+    ) -> error::Result<(Vec<usize>, Vec<usize>)> {
+        let putting = nelems.is_some();
+        let elemlen = match self.dimensions.last() {
+            Some(d) => d.len(),
+            None => return Err("variable has no dimensions".into()),
+        };
+
+        let indices = if let Some(x) = indices {
+            let mut indices = x.to_vec();
+            indices.push(0);
+            self.check_indices(&indices, putting)?;
+            indices
+        } else {
+            self.default_indices(putting)?
+        };
+        let slice_len = if let Some(x) = slice_len {
+            let mut slice_len = x.to_vec();
+            slice_len.push(elemlen);
+            let totallen = match nelems {
+                Some(n) => n.saturating_mul(elemlen),
+                None => slice_len
+                    .iter()
+                    .fold(1_usize, |acc, &x| acc.saturating_mul(x)),
+            };
+            self.check_sizelen(totallen, &indices, &slice_len, putting)?;
+            slice_len
+        } else {
+            let totallen = match nelems {
+                Some(n) => n.saturating_mul(elemlen),
+                None => self
+                    .dimensions
+                    .iter()
+                    .zip(&indices)
+                    .fold(1_usize, |acc, (d, &i)| {
+                        acc.saturating_mul(d.len().saturating_sub(i))
+                    }),
+            };
+            self.default_sizelen(totallen, &indices, putting)?
+        };
+
+        Ok((indices, slice_len))
+    }
+
+    /// This variable with `Conversion::Strict` relaxed to `Conversion::Lossy`,
+    /// for helpers which convert between types by design (such as unpacking)
+    pub(crate) fn relaxed(&self) -> Cow<'_, Self> {
+        if self.conversion == Conversion::Strict {
+            Cow::Owned(Self {
+                conversion: Conversion::Lossy,
+                ..self.clone()
+            })
+        } else {
+            Cow::Borrowed(self)
+        }
+    }
+
+    /// Calls `f` with `Conversion::Strict` relaxed to `Conversion::Lossy`,
+    /// like [`Variable::relaxed`] but changing this handle in place
+    pub(crate) fn with_relaxed<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let conversion = self.conversion;
+        if conversion == Conversion::Strict {
+            self.conversion = Conversion::Lossy;
+        }
+        let result = f(self);
+        self.conversion = conversion;
+        result
+    }
+
+    /// Checks the conversion policy before reading or writing values of type `T`
+    pub(crate) fn check_conversion<T: Numeric>(&self) -> error::Result<()> {
+        if self.conversion() == Conversion::Strict && T::NCTYPE != self.vartype {
+            return Err(error::Error::TypeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Finds the positions of the values which can not be represented in
+    /// the type of the variable, when using `Conversion::Checked`
+    pub(crate) fn check_range_put<'a, T: Numeric + 'a>(
+        &self,
+        values: impl Iterator<Item = (usize, &'a T)>,
+    ) -> error::Result<()> {
+        if self.conversion() != Conversion::Checked {
+            return Ok(());
+        }
+        let pos = values
+            .filter(|(_, v)| !v.representable_as(self.vartype))
+            .map(|(p, _)| p)
+            .collect::<Vec<_>>();
+        if pos.is_empty() {
+            Ok(())
+        } else {
+            Err(error::Error::OutOfRange(pos))
+        }
+    }
+
+    /// Turns a range error from reading into `Error::OutOfRange` when
+    /// using `Conversion::Checked`. The positions are counted in the
+    /// requested slice, with the last dimension varying the fastest.
+    /// A missing filter is reported as `Error::MissingFilter`
+    pub(crate) fn check_range_get<T: Numeric>(
+        &self,
+        result: error::Result<()>,
+        indices: &[usize],
+        slice_len: &[usize],
+        strides: Option<&[isize]>,
+    ) -> error::Result<()> {
+        match result {
+            Err(error::Error::Netcdf(NC_ERANGE)) if self.conversion() == Conversion::Checked => {
+                Err(error::Error::OutOfRange(
+                    self.out_of_range::<T>(indices, slice_len, strides)?,
+                ))
+            }
+            #[cfg(feature = "filters")]
+            Err(error::Error::Netcdf(NC_ENOFILTER)) => Err(self.missing_filter()),
+            r => r,
+        }
+    }
+
+    /// Reads the slice using the type of the variable, and finds
+    /// the values which can not be represented by `T`
+    fn out_of_range<T: Numeric>(
+        &self,
+        indices: &[usize],
+        slice_len: &[usize],
+        strides: Option<&[isize]>,
+    ) -> error::Result<Vec<usize>> {
+        fn positions<S: Numeric, T: Numeric>(
+            variable: &Variable,
+            indices: &[usize],
+            slice_len: &[usize],
+            strides: Option<&[isize]>,
+        ) -> error::Result<Vec<usize>> {
+            let len = slice_len.iter().product();
+            let mut values: Vec<S> = Vec::with_capacity(len);
+            unsafe {
+                if let Some(strides) = strides {
+                    S::get_values_strided(
+                        variable,
+                        indices,
+                        slice_len,
+                        strides,
+                        values.as_mut_ptr(),
+                    )?;
+                } else {
+                    S::variable_to_ptr(variable, indices, slice_len, values.as_mut_ptr())?;
+                }
+                values.set_len(len);
+            }
+            Ok(values
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| !v.representable_as(T::NCTYPE))
+                .map(|(p, _)| p)
+                .collect())
+        }
+
+        match self.vartype {
+            NC_BYTE => positions::<i8, T>(self, indices, slice_len, strides),
+            NC_UBYTE => positions::<u8, T>(self, indices, slice_len, strides),
+            NC_SHORT => positions::<i16, T>(self, indices, slice_len, strides),
+            NC_USHORT => positions::<u16, T>(self, indices, slice_len, strides),
+            NC_INT => positions::<i32, T>(self, indices, slice_len, strides),
+            NC_UINT => positions::<u32, T>(self, indices, slice_len, strides),
+            NC_INT64 => positions::<i64, T>(self, indices, slice_len, strides),
+            NC_UINT64 => positions::<u64, T>(self, indices, slice_len, strides),
+            NC_FLOAT => positions::<f32, T>(self, indices, slice_len, strides),
+            NC_DOUBLE => positions::<f64, T>(self, indices, slice_len, strides),
+            x => Err(error::Error::TypeUnknown(x)),
+        }
+    }
+}
+
+/// Offset in a mapped buffer of the element at position `pos`
+/// in a slice (with the last dimension varying the fastest)
+fn mapped_offset(mut pos: usize, slice_len: &[usize], map: &[isize]) -> usize {
+    let mut offset = 0;
+    for (&s, &m) in slice_len.iter().zip(map).rev() {
+        offset += (pos % s) * m as usize;
+        pos /= s;
+    }
+    offset
+}
+
+/// A run of points which can be read with a single call
+#[derive(Debug, PartialEq)]
+struct Run {
+    /// Index of the first point of the run
+    start: Vec<usize>,
+    /// Number of elements along the last dimension
+    len: usize,
+    /// Position of each point in the input, and its offset in the run
+    members: Vec<(usize, usize)>,
+}
+
+/// Groups `points` into runs which only differ in the last index,
+/// where these indices are adjacent (or repeated)
+fn coalesce(points: &[&[usize]]) -> Vec<Run> {
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| points[i]);
+
+    let mut runs: Vec<Run> = Vec::new();
+    for i in order {
+        let point = points[i];
+        if let Some(run) = runs.last_mut() {
+            let (prefix, last) = match point.split_last() {
+                Some((&last, prefix)) => (prefix, last),
+                None => (point, 0),
+            };
+            let run_last = run.start.last().copied().unwrap_or(0);
+            if prefix == &run.start[..prefix.len()] && last <= run_last + run.len {
+                let offset = last - run_last;
+                run.len = run.len.max(offset + 1);
+                run.members.push((i, offset));
+                continue;
+            }
+        }
+        runs.push(Run {
+            start: point.to_vec(),
+            len: 1,
+            members: vec![(i, 0)],
+        });
+    }
+    runs
+}
+
+#[test]
+fn point_runs() {
+    let points: [&[usize]; 6] = [&[1, 3], &[0, 5], &[1, 2], &[1, 3], &[0, 7], &[1, 4]];
+    assert_eq!(
+        coalesce(&points),
+        [
+            Run {
+                start: vec![0, 5],
+                len: 1,
+                members: vec![(1, 0)]
+            },
+            Run {
+                start: vec![0, 7],
+                len: 1,
+                members: vec![(4, 0)]
+            },
+            Run {
+                start: vec![1, 2],
+                len: 3,
+                members: vec![(2, 0), (0, 1), (3, 1), (5, 2)]
+            },
+        ]
+    );
+    let scalar: [&[usize]; 2] = [&[], &[]];
+    assert_eq!(
+        coalesce(&scalar),
+        [Run {
+            start: vec![],
+            len: 1,
+            members: vec![(0, 0), (1, 0)]
+        }]
+    );
+}
+
+/// A numeric value widened to a type which can hold
+/// all values of the netcdf numeric types
+#[derive(Debug, Copy, Clone)]
+enum Wide {
+    Integer(i128),
+    Float(f64),
+}
+
+impl Wide {
+    /// Checks whether the value over- or underflows the netcdf type
+    fn representable_as(self, nctype: nc_type) -> bool {
+        match self {
+            Self::Integer(i) => match nctype {
+                NC_BYTE => i8::try_from(i).is_ok(),
+                NC_UBYTE => u8::try_from(i).is_ok(),
+                NC_SHORT => i16::try_from(i).is_ok(),
+                NC_USHORT => u16::try_from(i).is_ok(),
+                NC_INT => i32::try_from(i).is_ok(),
+                NC_UINT => u32::try_from(i).is_ok(),
+                NC_INT64 => i64::try_from(i).is_ok(),
+                NC_UINT64 => u64::try_from(i).is_ok(),
+                NC_FLOAT | NC_DOUBLE => true,
+                _ => false,
+            },
+            Self::Float(x) => {
+                // Fractional parts are truncated, not out of range.
+                // The bounds are powers of two, which are exact as f64
+                // (unlike `i64::MAX` and `u64::MAX`), with `end` excluded
+                let fits = |min: f64, end: f64| x.trunc() >= min && x.trunc() < end;
+                let pow2 = |bits: i32| 2.0_f64.powi(bits);
+                match nctype {
+                    NC_DOUBLE => true,
+                    NC_FLOAT => !x.is_finite() || x.abs() <= f64::from(f32::MAX),
+                    NC_BYTE => fits(-pow2(7), pow2(7)),
+                    NC_UBYTE => fits(0.0, pow2(8)),
+                    NC_SHORT => fits(-pow2(15), pow2(15)),
+                    NC_USHORT => fits(0.0, pow2(16)),
+                    NC_INT => fits(-pow2(31), pow2(31)),
+                    NC_UINT => fits(0.0, pow2(32)),
+                    NC_INT64 => fits(-pow2(63), pow2(63)),
+                    NC_UINT64 => fits(0.0, pow2(64)),
+                    _ => false,
+                }
+            }
         }
-        Ok(sizelen)
     }
 }
 
@@ -294,6 +1352,11 @@ where
     /// Constant corresponding to a netcdf type
     const NCTYPE: nc_type;
 
+    #[doc(hidden)]
+    /// Whether this value can be represented by the netcdf type `nctype`
+    /// without over- or underflowing
+    fn representable_as(&self, nctype: nc_type) -> bool;
+
     /// Returns a single indexed value of the variable as Self
     ///
     /// # Safety
@@ -318,6 +1381,15 @@ where
         values: *mut Self,
     ) -> error::Result<()>;
 
+    #[doc(hidden)]
+    /// Same as `variable_to_ptr`, for use while holding the lock
+    unsafe fn variable_to_ptr_locked(
+        variable: &Variable,
+        indices: &[usize],
+        slice_len: &[usize],
+        values: *mut Self,
+    ) -> error::Result<()>;
+
     #[allow(clippy::doc_markdown)]
     /// Put a single value into a netCDF variable
     ///
@@ -362,6 +1434,38 @@ where
         strides: &[isize],
         values: *const Self,
     ) -> error::Result<()>;
+
+    /// get a SLICE of values from the variable, with the memory
+    /// layout of `values` given by `map`
+    ///
+    /// # Safety
+    ///
+    /// Requires `values` to be able to hold all elements addressed
+    /// through `map`, and `indices`, `slice_len`, `map` to be of a
+    /// valid length
+    unsafe fn get_values_mapped(
+        variable: &Variable,
+        indices: &[usize],
+        slice_len: &[usize],
+        map: &[isize],
+        values: *mut Self,
+    ) -> error::Result<()>;
+
+    /// put a SLICE of values into the variable, with the memory
+    /// layout of `values` given by `map`
+    ///
+    /// # Safety
+    ///
+    /// Requires `values` to hold all elements addressed
+    /// through `map`, and `indices`, `slice_len`, `map` to be of a
+    /// valid length
+    unsafe fn put_values_mapped(
+        variable: &mut Variable,
+        indices: &[usize],
+        slice_len: &[usize],
+        map: &[isize],
+        values: *const Self,
+    ) -> error::Result<()>;
 }
 
 #[allow(clippy::doc_markdown)]
@@ -374,6 +1478,7 @@ macro_rules! impl_numeric {
     (
         $sized_type: ty,
         $nc_type: ident,
+        $wide: ident,
         $nc_get_var: ident,
         $nc_get_vara_type: ident,
         $nc_get_var1_type: ident,
@@ -381,11 +1486,17 @@ macro_rules! impl_numeric {
         $nc_put_vara_type: ident,
         $nc_get_vars_type: ident,
         $nc_put_vars_type: ident,
+        $nc_get_varm_type: ident,
+        $nc_put_varm_type: ident,
     ) => {
         #[allow(clippy::use_self)] // False positives
         unsafe impl Numeric for $sized_type {
             const NCTYPE: nc_type = $nc_type;
 
+            fn representable_as(&self, nctype: nc_type) -> bool {
+                Wide::$wide(*self as _).representable_as(nctype)
+            }
+
             // fetch ONE value from variable using `$nc_get_var1`
             unsafe fn single_value_from_variable(
                 variable: &Variable,
@@ -412,7 +1523,15 @@ macro_rules! impl_numeric {
                 values: *mut Self,
             ) -> error::Result<()> {
                 let _l = LOCK.lock().unwrap();
+                Self::variable_to_ptr_locked(variable, indices, slice_len, values)
+            }
 
+            unsafe fn variable_to_ptr_locked(
+                variable: &Variable,
+                indices: &[usize],
+                slice_len: &[usize],
+                values: *mut Self,
+            ) -> error::Result<()> {
                 error::checked($nc_get_vara_type(
                     variable.ncid,
                     variable.varid,
@@ -489,12 +1608,51 @@ macro_rules! impl_numeric {
                     values,
                 ))
             }
+
+            unsafe fn get_values_mapped(
+                variable: &Variable,
+                indices: &[usize],
+                slice_len: &[usize],
+                map: &[isize],
+                values: *mut Self,
+            ) -> error::Result<()> {
+                let _l = LOCK.lock().unwrap();
+                error::checked($nc_get_varm_type(
+                    variable.ncid,
+                    variable.varid,
+                    indices.as_ptr(),
+                    slice_len.as_ptr(),
+                    std::ptr::null(),
+                    map.as_ptr(),
+                    values,
+                ))
+            }
+
+            unsafe fn put_values_mapped(
+                variable: &mut Variable,
+                indices: &[usize],
+                slice_len: &[usize],
+                map: &[isize],
+                values: *const Self,
+            ) -> error::Result<()> {
+                let _l = LOCK.lock().unwrap();
+                error::checked($nc_put_varm_type(
+                    variable.ncid,
+                    variable.varid,
+                    indices.as_ptr(),
+                    slice_len.as_ptr(),
+                    std::ptr::null(),
+                    map.as_ptr(),
+                    values,
+                ))
+            }
         }
     };
 }
 impl_numeric!(
     u8,
     NC_UBYTE,
+    Integer,
     nc_get_var_uchar,
     nc_get_vara_uchar,
     nc_get_var1_uchar,
@@ -502,11 +1660,14 @@ impl_numeric!(
     nc_put_vara_uchar,
     nc_get_vars_uchar,
     nc_put_vars_uchar,
+    nc_get_varm_uchar,
+    nc_put_varm_uchar,
 );
 
 impl_numeric!(
     i8,
     NC_BYTE,
+    Integer,
     nc_get_var_schar,
     nc_get_vara_schar,
     nc_get_var1_schar,
@@ -514,11 +1675,14 @@ impl_numeric!(
     nc_put_vara_schar,
     nc_get_vars_schar,
     nc_put_vars_schar,
+    nc_get_varm_schar,
+    nc_put_varm_schar,
 );
 
 impl_numeric!(
     i16,
     NC_SHORT,
+    Integer,
     nc_get_var_short,
     nc_get_vara_short,
     nc_get_var1_short,
@@ -526,11 +1690,14 @@ impl_numeric!(
     nc_put_vara_short,
     nc_get_vars_short,
     nc_put_vars_short,
+    nc_get_varm_short,
+    nc_put_varm_short,
 );
 
 impl_numeric!(
     u16,
     NC_USHORT,
+    Integer,
     nc_get_var_ushort,
     nc_get_vara_ushort,
     nc_get_var1_ushort,
@@ -538,11 +1705,14 @@ impl_numeric!(
     nc_put_vara_ushort,
     nc_get_vars_ushort,
     nc_put_vars_ushort,
+    nc_get_varm_ushort,
+    nc_put_varm_ushort,
 );
 
 impl_numeric!(
     i32,
     NC_INT,
+    Integer,
     nc_get_var_int,
     nc_get_vara_int,
     nc_get_var1_int,
@@ -550,11 +1720,14 @@ impl_numeric!(
     nc_put_vara_int,
     nc_get_vars_int,
     nc_put_vars_int,
+    nc_get_varm_int,
+    nc_put_varm_int,
 );
 
 impl_numeric!(
     u32,
     NC_UINT,
+    Integer,
     nc_get_var_uint,
     nc_get_vara_uint,
     nc_get_var1_uint,
@@ -562,11 +1735,14 @@ impl_numeric!(
     nc_put_vara_uint,
     nc_get_vars_uint,
     nc_put_vars_uint,
+    nc_get_varm_uint,
+    nc_put_varm_uint,
 );
 
 impl_numeric!(
     i64,
     NC_INT64,
+    Integer,
     nc_get_var_longlong,
     nc_get_vara_longlong,
     nc_get_var1_longlong,
@@ -574,11 +1750,14 @@ impl_numeric!(
     nc_put_vara_longlong,
     nc_get_vars_longlong,
     nc_put_vars_longlong,
+    nc_get_varm_longlong,
+    nc_put_varm_longlong,
 );
 
 impl_numeric!(
     u64,
     NC_UINT64,
+    Integer,
     nc_get_var_ulonglong,
     nc_get_vara_ulonglong,
     nc_get_var1_ulonglong,
@@ -586,11 +1765,14 @@ impl_numeric!(
     nc_put_vara_ulonglong,
     nc_get_vars_ulonglong,
     nc_put_vars_ulonglong,
+    nc_get_varm_ulonglong,
+    nc_put_varm_ulonglong,
 );
 
 impl_numeric!(
     f32,
     NC_FLOAT,
+    Float,
     nc_get_var_float,
     nc_get_vara_float,
     nc_get_var1_float,
@@ -598,11 +1780,14 @@ impl_numeric!(
     nc_put_vara_float,
     nc_get_vars_float,
     nc_put_vars_float,
+    nc_get_varm_float,
+    nc_put_varm_float,
 );
 
 impl_numeric!(
     f64,
     NC_DOUBLE,
+    Float,
     nc_get_var_double,
     nc_get_vara_double,
     nc_get_var1_double,
@@ -610,6 +1795,8 @@ impl_numeric!(
     nc_put_vara_double,
     nc_get_vars_double,
     nc_put_vars_double,
+    nc_get_varm_double,
+    nc_put_varm_double,
 );
 
 /// Holds the contents of a netcdf string. Use deref to get a `CStr`
@@ -629,6 +1816,15 @@ impl Drop for NcString {
         }
     }
 }
+/// Holds the strings read by `nc_get_vara_string`, which are freed on drop
+struct NcStrings(Vec<*mut std::os::raw::c_char>);
+impl Drop for NcStrings {
+    fn drop(&mut self) {
+        unsafe {
+            error::checked(nc_free_string(self.0.len(), self.0.as_mut_ptr())).unwrap();
+        }
+    }
+}
 impl std::ops::Deref for NcString {
     type Target = CStr;
     fn deref(&self) -> &Self::Target {
@@ -666,6 +1862,7 @@ impl Variable {
             vartype,
             ncid: grp_id,
             varid: id,
+            conversion: Conversion::default(),
         })
     }
 
@@ -689,11 +1886,79 @@ impl Variable {
             &indices_
         };
 
-        unsafe { T::single_value_from_variable(self, indices) }
+        self.check_conversion::<T>()?;
+        unsafe { T::single_value_from_variable(self, indices) }.map_err(|e| match e {
+            error::Error::Netcdf(NC_ERANGE) if self.conversion() == Conversion::Checked => {
+                error::Error::OutOfRange(vec![0])
+            }
+            #[cfg(feature = "filters")]
+            error::Error::Netcdf(NC_ENOFILTER) => self.missing_filter(),
+            e => e,
+        })
+    }
+
+    /// Fetches the values at many scattered `points`, in the order
+    /// given. Points which only differ in the last index, and where
+    /// these are adjacent, are read with a single call into `libnetcdf`,
+    /// and the lock is only taken once for all the reads.
+    ///
+    /// Values out of range for `T` (when the conversion is
+    /// [`Conversion::Checked`]) are reported by their position in `points`
+    pub fn gather<T: Numeric + Copy>(&self, points: &[&[usize]]) -> error::Result<Vec<T>> {
+        self.check_conversion::<T>()?;
+        let lens = self
+            .dimensions
+            .iter()
+            .map(Dimension::len)
+            .collect::<Vec<_>>();
+        for point in points {
+            if point.len() != lens.len() {
+                return Err(error::Error::IndexLen);
+            }
+            if point.iter().zip(&lens).any(|(i, len)| i >= len) {
+                return Err(error::Error::IndexMismatch);
+            }
+        }
+
+        let mut values: Vec<Option<T>> = vec![None; points.len()];
+        let result = {
+            let _l = LOCK.lock().unwrap();
+            coalesce(points).into_iter().try_for_each(|run| {
+                let mut slice_len = vec![1; run.start.len()];
+                if let Some(last) = slice_len.last_mut() {
+                    *last = run.len;
+                }
+                let mut buffer: Vec<T> = Vec::with_capacity(run.len);
+                unsafe {
+                    T::variable_to_ptr_locked(self, &run.start, &slice_len, buffer.as_mut_ptr())?;
+                    buffer.set_len(run.len);
+                }
+                for (i, offset) in run.members {
+                    values[i] = Some(buffer[offset]);
+                }
+                Ok(())
+            })
+        };
+        match result {
+            Err(error::Error::Netcdf(NC_ERANGE)) if self.conversion() == Conversion::Checked => {
+                let ones = vec![1; lens.len()];
+                let mut positions = Vec::new();
+                for (i, point) in points.iter().enumerate() {
+                    if !self.out_of_range::<T>(point, &ones, None)?.is_empty() {
+                        positions.push(i);
+                    }
+                }
+                Err(error::Error::OutOfRange(positions))
+            }
+            #[cfg(feature = "filters")]
+            Err(error::Error::Netcdf(NC_ENOFILTER)) => Err(self.missing_filter()),
+            r => r.map(|()| values.into_iter().map(Option::unwrap).collect()),
+        }
     }
 
     /// Reads a string variable. This involves two copies per read, and should
-    /// be avoided in performance critical code
+    /// be avoided in performance critical code. Use
+    /// [`Variable::string_values`] when reading multiple strings
     pub fn string_value(&self, indices: Option<&[usize]>) -> error::Result<String> {
         let indices_: Vec<usize>;
         let indices = if let Some(x) = indices {
@@ -718,6 +1983,43 @@ impl Variable {
         Ok(string.to_string_lossy().into_owned())
     }
 
+    /// Reads multiple strings from a string variable, using a
+    /// single call into `libnetcdf`. The strings are given with the
+    /// last dimension varying the fastest
+    pub fn string_values(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<Vec<String>> {
+        let (indices, slice_len) = self.read_extents(indices, slice_len)?;
+        let len = slice_len.iter().product();
+
+        let _l = LOCK.lock().unwrap();
+        // Freed on drop, also when the read fails after filling some of the strings
+        let mut ptrs = NcStrings(vec![std::ptr::null_mut(); len]);
+        unsafe {
+            error::checked(nc_get_vara_string(
+                self.ncid,
+                self.varid,
+                indices.as_ptr(),
+                slice_len.as_ptr(),
+                ptrs.0.as_mut_ptr(),
+            ))?;
+        }
+        let strings = ptrs
+            .0
+            .iter()
+            .map(|&s| {
+                if s.is_null() {
+                    String::new()
+                } else {
+                    unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+                }
+            })
+            .collect();
+        Ok(strings)
+    }
+
     #[cfg(feature = "ndarray")]
     /// Fetches variable
     pub fn values<T: Numeric>(
@@ -725,6 +2027,7 @@ impl Variable {
         indices: Option<&[usize]>,
         slice_len: Option<&[usize]>,
     ) -> error::Result<ArrayD<T>> {
+        self.check_conversion::<T>()?;
         let indices_: Vec<usize>;
         let indices = if let Some(x) = indices {
             self.check_indices(x, false)?;
@@ -750,12 +2053,96 @@ impl Variable {
 
         let mut values = Vec::with_capacity(full_length);
         unsafe {
-            T::variable_to_ptr(self, indices, slice_len, values.as_mut_ptr())?;
+            let r = T::variable_to_ptr(self, indices, slice_len, values.as_mut_ptr());
+            self.check_range_get::<T>(r, indices, slice_len, None)?;
             values.set_len(full_length);
         }
         Ok(ArrayD::from_shape_vec(slice_len, values).unwrap())
     }
 
+    #[cfg(feature = "ndarray")]
+    /// Iterates over the variable in blocks aligned to its chunks, or
+    /// in blocks of the shape `block` if given, which is required for
+    /// variables which are not chunked. Blocks at the end of a dimension
+    /// may be shorter, and only one block is held in memory at a time.
+    ///
+    /// The lengths of the dimensions are fixed when creating the iterator
+    pub fn chunks<T: Numeric>(&self, block: Option<&[usize]>) -> error::Result<Chunks<'_, T>> {
+        let block = match (block, self.storage()?) {
+            (Some(block), _) => block.to_vec(),
+            (None, Storage::Chunked(chunksize)) => chunksize,
+            (None, _) => {
+                return Err("a block shape is required for variables which are not chunked".into())
+            }
+        };
+        if block.len() != self.dimensions.len() {
+            return Err(error::Error::SliceLen);
+        }
+        if block.contains(&0) {
+            return Err(error::Error::ZeroSlice);
+        }
+        let lens = self
+            .dimensions
+            .iter()
+            .map(Dimension::len)
+            .collect::<Vec<_>>();
+        let next = if lens.contains(&0) {
+            None
+        } else {
+            Some(vec![0; lens.len()])
+        };
+        Ok(Chunks {
+            variable: self,
+            block,
+            lens,
+            next,
+            _type: std::marker::PhantomData,
+        })
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Iterates over the records of the variable, i.e. the slices
+    /// along its first unlimited dimension. Any other unlimited
+    /// dimensions are read in full.
+    ///
+    /// The number of records is fixed when creating the iterator
+    pub fn records<T: Numeric>(&self) -> error::Result<Records<'_, T>> {
+        let dimension = self
+            .dimensions
+            .iter()
+            .position(Dimension::is_unlimited)
+            .ok_or_else(|| error::Error::NotFound("unlimited dimension".into()))?;
+        Ok(Records {
+            variable: self,
+            dimension,
+            lens: self.dimensions.iter().map(Dimension::len).collect(),
+            next: 0,
+            _type: std::marker::PhantomData,
+        })
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Fetches variable as an array of fixed dimensionality `D`,
+    /// e.g. `Ix2` for a variable with two dimensions.
+    /// A variable without dimensions (a scalar) is read using `Ix0`.
+    ///
+    /// Using `IxDyn` is equivalent to calling [`Variable::values`]
+    pub fn values_nd<T: Numeric, D: ndarray::Dimension>(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<ndarray::Array<T, D>> {
+        let rank = self.dimensions.len();
+        if let Some(ndim) = D::NDIM {
+            if ndim != rank {
+                return Err(error::Error::RankMismatch(ndim, rank));
+            }
+        }
+        self.values(indices, slice_len)?
+            .into_dimensionality::<D>()
+            .map_err(|_| error::Error::RankMismatch(D::NDIM.unwrap_or(rank), rank))
+    }
+
     /// Fetches variable into slice
     /// buffer must be able to hold all the requested elements
     pub fn values_to<T: Numeric>(
@@ -764,6 +2151,7 @@ impl Variable {
         indices: Option<&[usize]>,
         slice_len: Option<&[usize]>,
     ) -> error::Result<()> {
+        self.check_conversion::<T>()?;
         let indices_: Vec<usize>;
         let indices = if let Some(x) = indices {
             self.check_indices(x, false)?;
@@ -781,7 +2169,8 @@ impl Variable {
             &slice_len_
         };
 
-        unsafe { T::variable_to_ptr(self, indices, slice_len, buffer.as_mut_ptr()) }
+        let r = unsafe { T::variable_to_ptr(self, indices, slice_len, buffer.as_mut_ptr()) };
+        self.check_range_get::<T>(r, indices, slice_len, None)
     }
 
     /// Fetches variable into slice
@@ -793,6 +2182,7 @@ impl Variable {
         slice_len: Option<&[usize]>,
         strides: &[isize],
     ) -> error::Result<usize> {
+        self.check_conversion::<T>()?;
         if strides.len() != self.dimensions.len() {
             return Err("stride_mismatch".into());
         }
@@ -854,10 +2244,52 @@ impl Variable {
         if buffer.len() < slice_len.iter().product() {
             return Err("buffer too small".into());
         }
-        unsafe { T::get_values_strided(self, indices, &slice_len, strides, buffer.as_mut_ptr())? };
+        let r = unsafe {
+            T::get_values_strided(self, indices, &slice_len, strides, buffer.as_mut_ptr())
+        };
+        self.check_range_get::<T>(r, indices, slice_len, Some(strides))?;
         Ok(slice_len.iter().product())
     }
 
+    /// Fetches variable into slice, with the memory layout of `buffer`
+    /// described by `map`
+    ///
+    /// `map[i]` gives the distance (in number of elements) in `buffer`
+    /// between two consecutive elements along dimension `i`. As an example,
+    /// a variable with dimensions `[ny, nx]` can be read into a buffer in
+    /// Fortran (column-major) order by using the map `[1, ny]`.
+    /// The buffer must be able to hold every element addressed by `map`.
+    pub fn values_mapped_to<T: Numeric>(
+        &self,
+        buffer: &mut [T],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+        map: &[isize],
+    ) -> error::Result<()> {
+        self.check_conversion::<T>()?;
+        let indices_: Vec<usize>;
+        let indices = if let Some(x) = indices {
+            self.check_indices(x, false)?;
+            x
+        } else {
+            indices_ = self.default_indices(false)?;
+            &indices_
+        };
+        let slice_len = self.mapped_sizelen(buffer.len(), indices, slice_len, map, false)?;
+
+        let r =
+            unsafe { T::get_values_mapped(self, indices, &slice_len, map, buffer.as_mut_ptr()) };
+        self.check_range_get::<T>(r, indices, &slice_len, None)
+            .map_err(|e| match e {
+                error::Error::OutOfRange(pos) => error::Error::OutOfRange(
+                    pos.into_iter()
+                        .map(|p| mapped_offset(p, &slice_len, map))
+                        .collect(),
+                ),
+                e => e,
+            })
+    }
+
     /// Put a single value at `indices`
     pub fn put_value<T: Numeric>(
         &mut self,
@@ -872,11 +2304,13 @@ impl Variable {
             indices_ = self.default_indices(true)?;
             &indices_
         };
+        self.check_conversion::<T>()?;
+        self.check_range_put(std::iter::once((0, &value)))?;
         unsafe { T::put_value_at(self, indices, value) }
     }
 
     /// Internally converts to a `CString`, avoid using this function when performance
-    /// is important
+    /// is important. Use [`Variable::put_strings`] when putting multiple strings
     pub fn put_string(&mut self, value: &str, indices: Option<&[usize]>) -> error::Result<()> {
         let indices_: Vec<usize>;
         let indices = if let Some(x) = indices {
@@ -904,10 +2338,81 @@ impl Variable {
         Ok(())
     }
 
-    /// Put a slice of values at `indices`
-    pub fn put_values<T: Numeric>(
+    /// Reads a single character from a variable of type `NC_CHAR`
+    pub fn char_value(&self, indices: Option<&[usize]>) -> error::Result<u8> {
+        if self.vartype != NC_CHAR {
+            return Err(error::Error::TypeMismatch);
+        }
+        let indices_: Vec<usize>;
+        let indices = if let Some(x) = indices {
+            self.check_indices(x, false)?;
+            x
+        } else {
+            indices_ = self.default_indices(false)?;
+            &indices_
+        };
+
+        let mut c = 0_u8;
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_get_var1_text(
+                self.ncid,
+                self.varid,
+                indices.as_ptr(),
+                &mut c as *mut u8 as *mut _,
+            ))?;
+        }
+        Ok(c)
+    }
+
+    /// Reads a variable of type `NC_CHAR` as strings, with the
+    /// last dimension giving the (maximum) length of the strings.
+    /// This is the convention used for storing strings
+    /// in the classic format.
+    ///
+    /// `indices` and `slice_len` apply to the dimensions
+    /// preceding the string length dimension. The strings end at the
+    /// first NUL character
+    pub fn char_strings(
+        &self,
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<Vec<String>> {
+        let (indices, slice_len) = self.char_extents(indices, slice_len, None)?;
+        let strlen = *slice_len.last().unwrap();
+        let len = slice_len.iter().product();
+
+        let mut buf = vec![0_u8; len];
+        {
+            let _l = LOCK.lock().unwrap();
+            unsafe {
+                error::checked(nc_get_vara_text(
+                    self.ncid,
+                    self.varid,
+                    indices.as_ptr(),
+                    slice_len.as_ptr(),
+                    buf.as_mut_ptr() as *mut _,
+                ))?;
+            }
+        }
+
+        if strlen == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(buf
+            .chunks(strlen)
+            .map(|s| {
+                let end = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+                String::from_utf8_lossy(&s[..end]).into_owned()
+            })
+            .collect())
+    }
+
+    /// Put multiple strings into a string variable at `indices`,
+    /// using a single call into `libnetcdf`
+    pub fn put_strings(
         &mut self,
-        values: &[T],
+        values: &[&str],
         indices: Option<&[usize]>,
         slice_len: Option<&[usize]>,
     ) -> error::Result<()> {
@@ -927,7 +2432,77 @@ impl Variable {
             slice_len_ = self.default_sizelen(values.len(), indices, true)?;
             &slice_len_
         };
-        unsafe { T::put_values_at(self, indices, slice_len, values) }
+
+        let values = values
+            .iter()
+            .map(|&x| std::ffi::CString::new(x))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| error::Error::from("String contained interior 0"))?;
+        let mut ptrs = values.iter().map(|x| x.as_ptr()).collect::<Vec<_>>();
+
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_put_vara_string(
+                self.ncid,
+                self.varid,
+                indices.as_ptr(),
+                slice_len.as_ptr(),
+                ptrs.as_mut_ptr(),
+            ))
+        }
+    }
+
+    /// Put a slice of values at `indices`
+    pub fn put_values<T: Numeric>(
+        &mut self,
+        values: &[T],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        let (indices, slice_len) = self.put_extents(values.len(), indices, slice_len)?;
+        self.check_conversion::<T>()?;
+        self.check_range_put(values.iter().enumerate())?;
+        unsafe { T::put_values_at(self, &indices, &slice_len, values) }
+    }
+
+    /// Put strings into a variable of type `NC_CHAR`, where the
+    /// last dimension gives the maximum length of the strings.
+    /// Shorter strings are padded with NUL.
+    ///
+    /// `indices` and `slice_len` apply to the dimensions
+    /// preceding the string length dimension
+    pub fn put_char_strings(
+        &mut self,
+        values: &[&str],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+    ) -> error::Result<()> {
+        let (indices, slice_len) = self.char_extents(indices, slice_len, Some(values.len()))?;
+        let strlen = *slice_len.last().unwrap();
+
+        let mut buf = vec![0_u8; values.len() * strlen];
+        for (value, b) in values.iter().zip(buf.chunks_mut(strlen)) {
+            if value.len() > strlen {
+                return Err(format!(
+                    "string of length {} does not fit in length {}",
+                    value.len(),
+                    strlen
+                )
+                .into());
+            }
+            b[..value.len()].copy_from_slice(value.as_bytes());
+        }
+
+        let _l = LOCK.lock().unwrap();
+        unsafe {
+            error::checked(nc_put_vara_text(
+                self.ncid,
+                self.varid,
+                indices.as_ptr(),
+                slice_len.as_ptr(),
+                buf.as_ptr() as *const _,
+            ))
+        }
     }
 
     /// Put a slice of values at `indices`, with destination strided
@@ -997,13 +2572,49 @@ impl Variable {
                 .collect();
             &slice_len_
         };
-        if values.len() < slice_len.iter().product() {
+        let len = slice_len.iter().product();
+        if values.len() < len {
             return Err("not enough values".into());
         }
+        self.check_conversion::<T>()?;
+        self.check_range_put(values[..len].iter().enumerate())?;
         unsafe { T::put_values_strided(self, indices, slice_len, strides, values.as_ptr())? };
         Ok(slice_len.iter().product())
     }
 
+    /// Put a slice of values at `indices`, with the memory layout of
+    /// `values` described by `map`
+    ///
+    /// See [`Variable::values_mapped_to`] for the meaning of `map`.
+    /// `slice_len` must be given when putting beyond the current
+    /// length of an unlimited dimension
+    pub fn put_values_mapped<T: Numeric>(
+        &mut self,
+        values: &[T],
+        indices: Option<&[usize]>,
+        slice_len: Option<&[usize]>,
+        map: &[isize],
+    ) -> error::Result<()> {
+        let indices_: Vec<usize>;
+        let indices = if let Some(x) = indices {
+            self.check_indices(x, true)?;
+            x
+        } else {
+            indices_ = self.default_indices(true)?;
+            &indices_
+        };
+        let slice_len = self.mapped_sizelen(values.len(), indices, slice_len, map, true)?;
+
+        self.check_conversion::<T>()?;
+        let len = slice_len.iter().product();
+        self.check_range_put((0..len).map(|p| {
+            let offset = mapped_offset(p, &slice_len, map);
+            (offset, &values[offset])
+        }))?;
+
+        unsafe { T::put_values_mapped(self, indices, &slice_len, map, values.as_ptr()) }
+    }
+
     /// Set a Fill Value
     #[allow(clippy::needless_pass_by_value)] // All values will be small
     pub fn set_fill_value<T>(&mut self, fill_value: T) -> error::Result<()>
@@ -0,0 +1,762 @@
+#[test]
+fn packed_values() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("packed.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 4).unwrap();
+
+        let var = file.add_variable::<i16>("packed", &["x"]).unwrap();
+        assert_eq!(var.packing().unwrap(), None);
+        var.add_attribute("scale_factor", 0.01f32).unwrap();
+        var.add_attribute("add_offset", 273.15f32).unwrap();
+        var.add_attribute("_FillValue", -32767i16).unwrap();
+
+        let packing = var.packing().unwrap().unwrap();
+        assert_eq!(packing.unpacked_type, netcdf_sys::NC_FLOAT);
+        assert!(!packing.unsigned);
+
+        var.put_values_packed(&[273.15f64, 274.0, 263.264, f64::NAN], None, None)
+            .unwrap();
+
+        let var = file.add_variable::<i8>("unsigned", &["x"]).unwrap();
+        var.add_attribute("_Unsigned", "true").unwrap();
+        var.add_attribute("scale_factor", 0.5).unwrap();
+        var.put_values_packed(&[0.0f32, 1.0, 100.0, 127.5], None, None)
+            .unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+
+    let var = file.variable("packed").unwrap();
+    let mut raw = [0i16; 4];
+    var.values_to(&mut raw, None, None).unwrap();
+    assert_eq!(raw, [0, 85, -989, -32767]);
+
+    let mut values = [0.0f64; 4];
+    var.values_unpacked_to(&mut values, None, None).unwrap();
+    assert!((values[0] - 273.15).abs() < 1e-4);
+    assert!((values[1] - 274.0).abs() < 1e-4);
+    assert!((values[2] - 263.26).abs() < 1e-4);
+
+    // Unpacking converts the type by design
+    let mut strict = var.clone();
+    strict.set_conversion(netcdf::Conversion::Strict);
+    strict.values_unpacked_to(&mut values, None, None).unwrap();
+    assert!((values[1] - 274.0).abs() < 1e-4);
+    assert_eq!(
+        strict.values_to(&mut values, None, None).unwrap_err(),
+        netcdf::error::Error::TypeMismatch
+    );
+
+    let var = file.variable("unsigned").unwrap();
+    let mut raw = [0i8; 4];
+    var.values_to(&mut raw, None, None).unwrap();
+    assert_eq!(raw, [0, 2, -56, -1]);
+    let mut values = [0.0f32; 4];
+    var.values_unpacked_to(&mut values, None, None).unwrap();
+    assert_eq!(values, [0.0, 1.0, 100.0, 127.5]);
+
+    #[cfg(feature = "ndarray")]
+    {
+        let values = var.values_unpacked::<f64>(Some(&[2]), Some(&[2])).unwrap();
+        assert_eq!(values.as_slice().unwrap(), &[100.0, 127.5]);
+    }
+}
+
+#[test]
+fn masked_values() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("masked.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 6).unwrap();
+
+        let var = file.add_variable::<i16>("plain", &["x"]).unwrap();
+        var.add_attribute("_FillValue", -1i16).unwrap();
+        var.add_attribute("missing_value", -2i16).unwrap();
+        var.add_attribute("valid_range", vec![0i16, 100]).unwrap();
+        var.put_values(&[-1i16, -2, 5, 100, 101, -3], None, None)
+            .unwrap();
+
+        let var = file.add_variable::<i16>("packed", &["x"]).unwrap();
+        var.add_attribute("scale_factor", -0.5f64).unwrap();
+        var.add_attribute("valid_min", -10.0f64).unwrap();
+        var.put_values(&[0i16, 10, 20, 21, -4, 1], None, None)
+            .unwrap();
+
+        let var = file.add_variable::<f32>("default", &["x"]).unwrap();
+        var.put_values(&[1.0f32, 2.0], Some(&[0]), Some(&[2]))
+            .unwrap();
+
+        let var = file.add_variable::<i32>("int", &["x"]).unwrap();
+        var.put_values(&[7i32], Some(&[0]), Some(&[1])).unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+
+    let var = file.variable("plain").unwrap();
+    let mask = var.mask().unwrap();
+    assert_eq!(mask.fill_value, Some(-1.0));
+    assert_eq!(mask.missing_values, [-2.0]);
+    assert_eq!((mask.valid_min, mask.valid_max), (Some(0.0), Some(100.0)));
+    let mut values = [None; 6];
+    var.values_masked_to::<i16>(&mut values, None, None)
+        .unwrap();
+    assert_eq!(values, [None, None, Some(5), Some(100), None, None]);
+
+    let var = file.variable("packed").unwrap();
+    let mask = var.mask().unwrap();
+    assert_eq!((mask.valid_min, mask.valid_max), (None, Some(20.0)));
+    let mut values = [0.0f64; 6];
+    var.values_decoded_to(&mut values, None, None).unwrap();
+    assert_eq!(&values[..3], &[0.0, -5.0, -10.0]);
+    assert!(values[3].is_nan());
+    assert_eq!(&values[4..], &[2.0, -0.5]);
+
+    let var = file.variable("default").unwrap();
+    let mut values = [None; 6];
+    var.values_masked_to::<f32>(&mut values, None, None)
+        .unwrap();
+    assert_eq!(values, [Some(1.0), Some(2.0), None, None, None, None]);
+
+    // The default fill value of int is not exact in f32
+    let int = file.variable("int").unwrap();
+    let mut values = [0.0f32; 6];
+    int.values_decoded_to(&mut values, None, None).unwrap();
+    assert_eq!(values[0], 7.0);
+    assert!(values[1..].iter().all(|x| x.is_nan()));
+
+    #[cfg(feature = "ndarray")]
+    {
+        let values = var.values_decoded::<f64>(None, None).unwrap();
+        assert_eq!(values[0], 1.0);
+        assert!(values[5].is_nan());
+        let var = file.variable("plain").unwrap();
+        let values = var.values_masked::<i32>(Some(&[2]), Some(&[3])).unwrap();
+        assert_eq!(values.as_slice().unwrap(), &[Some(5), Some(100), None]);
+    }
+}
+
+#[test]
+fn time_coordinates() {
+    use netcdf::cf::time::{Calendar, DateTime, TimeUnit, TimeUnits};
+
+    let units = TimeUnits::parse("hours since 2000-01-01T06:00:00Z", Calendar::Day360).unwrap();
+    assert_eq!(units.unit, TimeUnit::Hours);
+    assert_eq!(units.epoch, DateTime::new(2000, 1, 1).and_hms(6, 0, 0.0));
+    assert_eq!(
+        units.decode(24.0 * 59.0),
+        Some(DateTime::new(2000, 2, 30).and_hms(6, 0, 0.0))
+    );
+    assert!(units.encode(&DateTime::new(2000, 2, 31)).is_err());
+    assert!(TimeUnits::parse("days since 1582-10-10", Calendar::Standard).is_err());
+    assert!(TimeUnits::parse("days after 1970-01-01", Calendar::Standard).is_err());
+
+    let units = TimeUnits::parse("days since 1582-10-04", Calendar::Standard).unwrap();
+    assert_eq!(units.decode(1.0), Some(DateTime::new(1582, 10, 15)));
+    let units = TimeUnits::parse("days since 1970-01-01 00:00 +01:00", Calendar::NoLeap).unwrap();
+    assert_eq!(
+        units.decode(0.0),
+        Some(DateTime::new(1969, 12, 31).and_hms(23, 0, 0.0))
+    );
+    assert_eq!(units.to_string(), "days since 1969-12-31 23:00:00");
+
+    // Dates too far from year zero are errors, not overflows
+    assert!(TimeUnits::parse("days since 100000000-01-01", Calendar::Standard).is_err());
+    let units = TimeUnits::parse("days since 200000-01-01", Calendar::NoLeap).unwrap();
+    assert_eq!(units.decode(365.0), Some(DateTime::new(200_001, 1, 1)));
+    assert_eq!(units.decode(1e8), None);
+    assert!(units.encode(&DateTime::new(-200_000, 1, 1)).is_err());
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("time.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("time", 4).unwrap();
+
+        let var = file.add_variable::<i32>("time", &["time"]).unwrap();
+        var.add_attribute("units", "days since 2000-01-01").unwrap();
+        var.add_attribute("calendar", "360_day").unwrap();
+        assert_eq!(
+            var.time_units().unwrap().unwrap().calendar,
+            Calendar::Day360
+        );
+        var.put_times(
+            &[
+                DateTime::new(2000, 1, 1),
+                DateTime::new(2000, 2, 30),
+                DateTime::new(2001, 12, 30),
+            ],
+            Some(&[0]),
+            Some(&[3]),
+        )
+        .unwrap();
+
+        let var = file.add_variable::<f64>("other", &["time"]).unwrap();
+        var.add_attribute("units", "m").unwrap();
+        assert_eq!(var.time_units().unwrap(), None);
+    }
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("time").unwrap();
+    let mut raw = [0; 3];
+    var.values_to(&mut raw, Some(&[0]), Some(&[3])).unwrap();
+    assert_eq!(raw, [0, 59, 719]);
+
+    let times = var.times(None, None).unwrap();
+    assert_eq!(
+        times,
+        [
+            Some(DateTime::new(2000, 1, 1)),
+            Some(DateTime::new(2000, 2, 30)),
+            Some(DateTime::new(2001, 12, 30)),
+            None
+        ]
+    );
+}
+
+#[test]
+fn coordinates() {
+    use netcdf::cf::{Axis, CoordinateKind};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("coordinates.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("time", 2).unwrap();
+        file.add_dimension("y", 3).unwrap();
+        file.add_dimension("x", 4).unwrap();
+
+        let var = file.add_variable::<f64>("time", &["time"]).unwrap();
+        var.add_attribute("units", "hours since 2000-01-01")
+            .unwrap();
+        let var = file.add_variable::<f64>("y", &["y"]).unwrap();
+        var.add_attribute("standard_name", "projection_y_coordinate")
+            .unwrap();
+        let var = file.add_variable::<f64>("x", &["x"]).unwrap();
+        var.add_attribute("axis", "X").unwrap();
+        let var = file.add_variable::<f32>("lat", &["y", "x"]).unwrap();
+        var.add_attribute("units", "degrees_north").unwrap();
+        let var = file.add_variable::<f32>("lon", &["y", "x"]).unwrap();
+        var.add_attribute("units", "degree_E").unwrap();
+        let var = file.add_variable::<f32>("level", &[]).unwrap();
+        var.add_attribute("positive", "up").unwrap();
+
+        let var = file
+            .add_variable::<f32>("temperature", &["time", "y", "x"])
+            .unwrap();
+        var.add_attribute("coordinates", "lat lon level").unwrap();
+
+        let group = file.root_mut().add_group("sub").unwrap();
+        let var = group.add_variable::<f32>("pressure", &["y", "x"]).unwrap();
+        var.add_attribute("coordinates", "/lat ../lon").unwrap();
+        let var = group.add_variable::<f32>("missing", &["x"]).unwrap();
+        var.add_attribute("coordinates", "nothing").unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+
+    let var = file.variable("temperature").unwrap();
+    let coordinates = var.coordinates().unwrap();
+    let found = coordinates
+        .iter()
+        .map(|c| (c.name.as_str(), c.kind, c.axis))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            ("time", CoordinateKind::Dimension, Some(Axis::T)),
+            ("y", CoordinateKind::Dimension, Some(Axis::Y)),
+            ("x", CoordinateKind::Dimension, Some(Axis::X)),
+            ("lat", CoordinateKind::Auxiliary, Some(Axis::Y)),
+            ("lon", CoordinateKind::Auxiliary, Some(Axis::X)),
+            ("level", CoordinateKind::Auxiliary, Some(Axis::Z)),
+        ]
+    );
+    let z = var.coordinate(Axis::Z).unwrap().unwrap();
+    assert_eq!(file.root().coordinate_variable(&z).unwrap().name(), "level");
+
+    // Coordinate variables are not coordinates of themselves
+    assert!(file
+        .variable("x")
+        .unwrap()
+        .coordinates()
+        .unwrap()
+        .is_empty());
+
+    let group = file.root().group("sub").unwrap();
+    let var = group.variable("pressure").unwrap();
+    let coordinates = var.coordinates().unwrap();
+    assert_eq!(coordinates.len(), 4);
+    assert_eq!(coordinates[2].name, "lat");
+    assert_eq!(coordinates[2].reference, "/lat");
+    assert_eq!(coordinates[3].name, "lon");
+    assert_eq!(coordinates[3].reference, "../lon");
+    let lon = group.coordinate_variable(&coordinates[3]).unwrap();
+    assert_eq!(lon.name(), "lon");
+    assert_eq!(lon.dimensions().len(), 2);
+
+    let var = group.variable("missing").unwrap();
+    assert_eq!(
+        var.coordinates(),
+        Err(netcdf::error::Error::NotFound(
+            "coordinate variable nothing".into()
+        ))
+    );
+}
+
+#[test]
+fn bounds_and_cell_methods() {
+    use netcdf::cf::{parse_cell_methods, Interval};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("bounds.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("time", 3).unwrap();
+        file.add_dimension("nv", 2).unwrap();
+
+        let var = file.add_variable::<f64>("time", &["time"]).unwrap();
+        var.add_attribute("bounds", "time_bnds").unwrap();
+        let var = file
+            .add_variable::<f64>("time_bnds", &["time", "nv"])
+            .unwrap();
+        var.put_values(&[0.0, 1.0, 1.0, 2.0, 2.0, 3.0], None, None)
+            .unwrap();
+        let var = file.add_variable::<f64>("nv", &["nv"]).unwrap();
+        var.add_attribute("bounds", "time_bnds").unwrap();
+
+        let var = file
+            .add_variable::<f32>("precipitation", &["time"])
+            .unwrap();
+        var.add_attribute(
+            "cell_methods",
+            "time: sum (interval: 1 hour comment: radar)",
+        )
+        .unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+
+    let var = file.variable("time").unwrap();
+    let bounds = var.bounds().unwrap().unwrap();
+    assert_eq!(bounds.name, "time_bnds");
+    assert_eq!(bounds.vertices, 2);
+    let bounds = file.root().bounds_variable(&bounds).unwrap();
+    let mut values = [0.0; 2];
+    bounds
+        .values_to(&mut values, Some(&[1, 0]), Some(&[1, 2]))
+        .unwrap();
+    assert_eq!(values, [1.0, 2.0]);
+
+    assert!(file.variable("nv").unwrap().bounds().is_err());
+    assert_eq!(file.variable("time_bnds").unwrap().bounds(), Ok(None));
+
+    let methods = file
+        .variable("precipitation")
+        .unwrap()
+        .cell_methods()
+        .unwrap();
+    assert_eq!(methods.len(), 1);
+    assert_eq!(methods[0].names, ["time"]);
+    assert_eq!(methods[0].method, "sum");
+    assert_eq!(
+        methods[0].intervals,
+        [Interval {
+            value: 1.0,
+            unit: "hour".into()
+        }]
+    );
+    assert_eq!(methods[0].comment.as_deref(), Some("radar"));
+    assert!(file
+        .variable("time")
+        .unwrap()
+        .cell_methods()
+        .unwrap()
+        .is_empty());
+
+    let methods =
+        parse_cell_methods("area: mean where sea_ice over sea time: maximum within days").unwrap();
+    assert_eq!(methods[0].where_type.as_deref(), Some("sea_ice"));
+    assert_eq!(methods[0].over_type.as_deref(), Some("sea"));
+    assert_eq!(methods[1].within.as_deref(), Some("days"));
+    assert_eq!(
+        methods[0].to_string() + " " + &methods[1].to_string(),
+        "area: mean where sea_ice over sea time: maximum within days"
+    );
+    assert!(parse_cell_methods("mean").is_err());
+}
+
+#[test]
+fn grid_mapping() {
+    use netcdf::cf::{Ellipsoid, GridMapping, ProjectionScale};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("grid_mapping.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("y", 2).unwrap();
+        file.add_dimension("x", 2).unwrap();
+
+        let var = file.add_variable::<i32>("polar", &[]).unwrap();
+        var.add_attribute("grid_mapping_name", "polar_stereographic")
+            .unwrap();
+        var.add_attribute("straight_vertical_longitude_from_pole", -45.0)
+            .unwrap();
+        var.add_attribute("latitude_of_projection_origin", 90.0)
+            .unwrap();
+        var.add_attribute("standard_parallel", 70.0).unwrap();
+        var.add_attribute("semi_major_axis", 6_378_273.0).unwrap();
+        var.add_attribute("semi_minor_axis", 6_356_889.449).unwrap();
+
+        let var = file.add_variable::<i32>("rotated_pole", &[]).unwrap();
+        var.add_attribute("grid_mapping_name", "rotated_latitude_longitude")
+            .unwrap();
+        var.add_attribute("grid_north_pole_latitude", 22.0).unwrap();
+        var.add_attribute("grid_north_pole_longitude", -40.0)
+            .unwrap();
+        var.add_attribute("earth_radius", 6_371_229.0).unwrap();
+
+        let var = file.add_variable::<i32>("lcc", &[]).unwrap();
+        var.add_attribute("grid_mapping_name", "lambert_conformal_conic")
+            .unwrap();
+        var.add_attribute("standard_parallel", vec![63.3, 63.3])
+            .unwrap();
+        var.add_attribute("longitude_of_central_meridian", 15.0)
+            .unwrap();
+        var.add_attribute("latitude_of_projection_origin", 63.3)
+            .unwrap();
+
+        let var = file.add_variable::<f32>("ice", &["y", "x"]).unwrap();
+        var.add_attribute("grid_mapping", "polar").unwrap();
+        let var = file.add_variable::<f32>("wind", &["y", "x"]).unwrap();
+        var.add_attribute("grid_mapping", "rotated_pole: rlat rlon")
+            .unwrap();
+        let var = file
+            .add_variable::<f32>("temperature", &["y", "x"])
+            .unwrap();
+        var.add_attribute("grid_mapping", "lcc").unwrap();
+        let var = file.add_variable::<f32>("broken", &["y", "x"]).unwrap();
+        var.add_attribute("grid_mapping", "temperature").unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+
+    let crs = file
+        .variable("ice")
+        .unwrap()
+        .grid_mapping()
+        .unwrap()
+        .unwrap();
+    assert_eq!(crs.name, "polar");
+    assert_eq!(
+        crs.grid_mapping,
+        GridMapping::PolarStereographic {
+            straight_vertical_longitude_from_pole: -45.0,
+            latitude_of_projection_origin: 90.0,
+            scale: ProjectionScale::StandardParallel(70.0),
+            false_easting: 0.0,
+            false_northing: 0.0,
+        }
+    );
+    assert_eq!(
+        crs.to_proj(),
+        "+proj=stere +lat_0=90 +lon_0=-45 +lat_ts=70 +x_0=0 +y_0=0 \
+         +a=6378273 +b=6356889.449 +units=m +no_defs"
+    );
+    let wkt = crs.to_wkt2();
+    assert!(wkt.starts_with("PROJCRS[\"polar\""));
+    assert!(wkt.contains("METHOD[\"Polar Stereographic (variant B)\",ID[\"EPSG\",9829]]"));
+
+    let crs = file
+        .variable("wind")
+        .unwrap()
+        .grid_mapping()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        crs.ellipsoid,
+        Some(Ellipsoid::Sphere {
+            radius: 6_371_229.0
+        })
+    );
+    assert_eq!(
+        crs.to_proj(),
+        "+proj=ob_tran +o_proj=longlat +o_lat_p=22 +o_lon_p=0 +lon_0=140 \
+         +R=6371229 +to_meter=0.0174532925199433 +no_defs"
+    );
+    assert!(crs
+        .to_wkt2()
+        .contains("METHOD[\"Pole rotation (netCDF CF convention)\"]"));
+
+    let crs = file
+        .variable("temperature")
+        .unwrap()
+        .grid_mapping()
+        .unwrap()
+        .unwrap();
+    assert_eq!(crs.ellipsoid, None);
+    assert_eq!(
+        crs.to_proj(),
+        "+proj=lcc +lat_0=63.3 +lon_0=15 +lat_1=63.3 +lat_2=63.3 +x_0=0 +y_0=0 \
+         +ellps=WGS84 +units=m +no_defs"
+    );
+    assert!(crs
+        .to_wkt2()
+        .contains("ELLIPSOID[\"unknown\",6378137,298.257223563,"));
+
+    assert_eq!(file.variable("polar").unwrap().grid_mapping(), Ok(None));
+    assert!(file.variable("broken").unwrap().grid_mapping().is_err());
+}
+
+#[test]
+fn flags() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("flags.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 4).unwrap();
+
+        let var = file.add_variable::<i8>("quality", &["x"]).unwrap();
+        var.add_attribute("flag_values", vec![0i8, 1, 2]).unwrap();
+        var.add_attribute("flag_meanings", "good suspect bad")
+            .unwrap();
+        var.put_values(&[0i8, 2, 1, 3], None, None).unwrap();
+
+        let var = file.add_variable::<u8>("status", &["x"]).unwrap();
+        var.add_attribute("flag_masks", vec![1u8, 2, 12, 12])
+            .unwrap();
+        var.add_attribute("flag_values", vec![1u8, 2, 4, 8])
+            .unwrap();
+        var.add_attribute("flag_meanings", "rain snow low_visibility fog")
+            .unwrap();
+        var.put_values(&[0u8, 3, 6, 9], None, None).unwrap();
+
+        let var = file.add_variable::<u8>("broken", &["x"]).unwrap();
+        var.add_attribute("flag_masks", vec![1u8, 2]).unwrap();
+        var.add_attribute("flag_meanings", "a b c").unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+
+    let var = file.variable("quality").unwrap();
+    let flags = var.flags().unwrap().unwrap();
+    assert_eq!(flags.flags.len(), 3);
+    assert_eq!(flags.flag("bad").unwrap().value, Some(2));
+    assert!(flags.is_set(1, "suspect"));
+    assert!(!flags.is_set(1, "missing"));
+    assert_eq!(
+        var.flag_meanings(None, None).unwrap(),
+        [vec!["good"], vec!["bad"], vec!["suspect"], vec![]]
+    );
+
+    let var = file.variable("status").unwrap();
+    let flags = var.flags().unwrap().unwrap();
+    assert_eq!(flags.active(13), ["rain", "fog"]);
+    assert_eq!(
+        var.flag_meanings(Some(&[1]), Some(&[3])).unwrap(),
+        [
+            vec!["rain", "snow"],
+            vec!["snow", "low_visibility"],
+            vec!["rain", "fog"]
+        ]
+    );
+
+    assert!(file.variable("broken").unwrap().flags().is_err());
+}
+
+#[test]
+fn ragged_arrays() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("ragged.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.root_mut()
+            .add_attribute("featureType", "timeSeries")
+            .unwrap();
+        file.add_dimension("station", 3).unwrap();
+        file.add_dimension("obs", 6).unwrap();
+        file.add_dimension("profile", 2).unwrap();
+        file.add_dimension("z", 5).unwrap();
+
+        let var = file.add_variable::<i32>("row_size", &["station"]).unwrap();
+        var.add_attribute("sample_dimension", "obs").unwrap();
+        var.put_values(&[2i32, 0, 4], None, None).unwrap();
+        let var = file.add_variable::<f32>("temperature", &["obs"]).unwrap();
+        var.put_values(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0], None, None)
+            .unwrap();
+
+        let group = file.root_mut().add_group("indexed").unwrap();
+        group.add_attribute("featureType", "Profile").unwrap();
+        let var = group.add_variable::<i32>("profile_index", &["z"]).unwrap();
+        var.add_attribute("instance_dimension", "profile").unwrap();
+        var.put_values(&[1i32, 0, -1, 1, 0], None, None).unwrap();
+        let var = group.add_variable::<i16>("salinity", &["z"]).unwrap();
+        var.put_values(&[10i16, 20, 30, 40, 50], None, None)
+            .unwrap();
+
+        let group = file.root_mut().add_group("malformed").unwrap();
+        group.add_dimension("n", 3).unwrap();
+        let var = group.add_variable::<i32>("counts", &["profile"]).unwrap();
+        var.add_attribute("sample_dimension", "n").unwrap();
+        var.put_values(&[2i32, 2], None, None).unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+    let root = file.root();
+    // The counts add up to more than the length of the sample dimension
+    assert!(root.group("malformed").unwrap().ragged_array("n").is_err());
+    assert_eq!(
+        root.feature_type().unwrap(),
+        Some(netcdf::cf::FeatureType::TimeSeries)
+    );
+    assert!(root.ragged_array("z").unwrap().is_none());
+
+    let ragged = root.ragged_array("obs").unwrap().unwrap();
+    assert!(ragged.is_contiguous());
+    assert_eq!(ragged.instance_dimension(), "station");
+    assert_eq!(ragged.len(), 3);
+    assert_eq!(ragged.samples(2), Some(vec![2, 3, 4, 5]));
+    assert_eq!(ragged.samples(3), None);
+    let temperature = file.variable("temperature").unwrap();
+    assert_eq!(
+        ragged.feature_values::<f64>(temperature, 2).unwrap(),
+        [3.0, 4.0, 5.0, 6.0]
+    );
+    assert_eq!(
+        ragged.values::<f32>(temperature).unwrap(),
+        [vec![1.0, 2.0], vec![], vec![3.0, 4.0, 5.0, 6.0]]
+    );
+    assert!(ragged
+        .values::<f32>(file.variable("row_size").unwrap())
+        .is_err());
+
+    let group = root.group("indexed").unwrap();
+    assert_eq!(
+        group.feature_type().unwrap(),
+        Some(netcdf::cf::FeatureType::Profile)
+    );
+    let ragged = group.ragged_array("z").unwrap().unwrap();
+    assert!(!ragged.is_contiguous());
+    assert_eq!(ragged.instance_dimension(), "profile");
+    let salinity = group.variable("salinity").unwrap();
+    assert_eq!(
+        ragged.values::<i16>(salinity).unwrap(),
+        [vec![20, 50], vec![10, 40]]
+    );
+    assert_eq!(ragged.feature_values::<i32>(salinity, 1).unwrap(), [10, 40]);
+}
+
+#[test]
+fn compliance_checker() {
+    use netcdf::cf::{check, Checker, Severity};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("check.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_attribute("Conventions", "CF-1.8").unwrap();
+        file.add_dimension("time", 3).unwrap();
+        file.add_dimension("x", 2).unwrap();
+
+        let var = file.add_variable::<f64>("time", &["time"]).unwrap();
+        var.add_attribute("standard_name", "time").unwrap();
+        var.add_attribute("units", "days since 2000-01-01").unwrap();
+        var.put_values(&[0.0, 1.0, 2.0], None, None).unwrap();
+
+        let var = file.add_variable::<f64>("x", &["x"]).unwrap();
+        var.add_attribute("units", "km").unwrap();
+        var.put_values(&[1.0, 1.0], None, None).unwrap();
+
+        let var = file
+            .add_variable::<f32>("temperature", &["time", "x"])
+            .unwrap();
+        var.add_attribute("standard_name", "air_temperature")
+            .unwrap();
+        var.add_attribute("units", "degC").unwrap();
+        var.add_attribute("_FillValue", -999.0f32).unwrap();
+
+        let group = file.root_mut().add_group("sub").unwrap();
+        let var = group.add_variable::<f32>("wind", &["time", "x"]).unwrap();
+        var.add_attribute("standard_name", "wind_speed standard_error")
+            .unwrap();
+        var.add_attribute("units", "m s-1").unwrap();
+        var.add_attribute("_FillValue", -999.0f64).unwrap();
+        var.add_attribute("coordinates", "lat").unwrap();
+        var.add_attribute("grid_mapping", "crs").unwrap();
+
+        let var = group.add_variable::<f32>("snow", &["time"]).unwrap();
+        var.add_attribute("standard_name", "snow_flake_count")
+            .unwrap();
+        var.add_attribute("units", "flakes").unwrap();
+        var.add_attribute("cell_methods", 1i32).unwrap();
+    }
+    let file = netcdf::open(&path).unwrap();
+
+    let issues = check(&file);
+    let found = issues
+        .iter()
+        .map(|i| {
+            (
+                i.severity,
+                i.variable.as_deref().unwrap(),
+                i.message.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            (
+                Severity::Error,
+                "x",
+                "coordinate variable is not strictly monotonic"
+            ),
+            (
+                Severity::Error,
+                "sub/wind",
+                "type of _FillValue does not match the variable"
+            ),
+            (
+                Severity::Error,
+                "sub/wind",
+                "grid_mapping: Could not find grid mapping variable crs"
+            ),
+            (
+                Severity::Error,
+                "sub/wind",
+                "coordinates: Could not find coordinate variable lat"
+            ),
+            (
+                Severity::Error,
+                "sub/snow",
+                "attribute cell_methods must be a string"
+            ),
+            (
+                Severity::Warning,
+                "sub/snow",
+                "units flakes could not be recognised"
+            ),
+        ]
+    );
+    assert_eq!(
+        issues[0].to_string(),
+        "error: x: coordinate variable is not strictly monotonic"
+    );
+
+    // Standard names are only checked against a given table
+    let table = r#"<standard_name_table>
+        <entry id="air_temperature"><canonical_units>K</canonical_units></entry>
+        <alias id="wind_speed"><entry_id>wind_speed</entry_id></alias>
+        </standard_name_table>"#;
+    let issues = Checker::new().with_standard_name_table(table).check(&file);
+    assert!(issues.iter().any(|i| i.severity == Severity::Error
+        && i.variable.as_deref() == Some("time")
+        && i.message == "unknown standard_name time"));
+
+    let table = r#"<standard_name_table>
+        <version_number>84</version_number>
+        <entry id="time"><canonical_units>s</canonical_units></entry>
+        </standard_name_table>"#;
+    let checker = Checker::new().with_standard_name_table(table);
+    assert_eq!(checker.standard_name_table_version(), Some("84"));
+    assert!(checker
+        .check(&file)
+        .iter()
+        .any(|i| i.severity == Severity::Error
+            && i.variable.as_deref() == Some("temperature")
+            && i.message == "unknown standard_name air_temperature (table version 84)"));
+}
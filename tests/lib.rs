@@ -655,6 +655,36 @@ fn fetch_ndarray() {
     assert_eq!(values_array.shape(), &[2, 2, 6, 12]);
 }
 
+#[test]
+#[cfg(feature = "ndarray")]
+fn fetch_ndarray_fixed_rank() {
+    use ndarray::{Ix0, Ix3, Ix4};
+    let f = test_location().join("pres_temp_4D.nc");
+    let file = netcdf::File::open(&f).unwrap();
+
+    let pres = file.variable("pressure").unwrap();
+    let values = pres.values_nd::<f64, Ix4>(None, None).unwrap();
+    assert_eq!(values.dim(), (2, 2, 6, 12));
+
+    let values = pres
+        .values_nd::<f64, Ix4>(Some(&[1, 0, 2, 3]), Some(&[1, 2, 3, 4]))
+        .unwrap();
+    assert_eq!(values.dim(), (1, 2, 3, 4));
+
+    assert_eq!(
+        pres.values_nd::<f64, Ix3>(None, None).unwrap_err(),
+        netcdf::error::Error::RankMismatch(3, 4)
+    );
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("fixed_rank_scalar.nc");
+    let mut file = netcdf::create(path).unwrap();
+    let var = file.add_variable::<f32>("scalar", &[]).unwrap();
+    var.put_value(4.5f32, None).unwrap();
+    let value = var.values_nd::<f32, Ix0>(None, None).unwrap();
+    assert_eq!(value.into_scalar(), 4.5);
+}
+
 #[test]
 // test file modification
 fn append() {
@@ -956,6 +986,274 @@ fn use_compression_chunking() {
     var.chunking(&[100, 2]).unwrap();
 }
 
+#[test]
+fn storage_layouts() {
+    use netcdf::Storage;
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("storage.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 10).unwrap();
+        file.add_unlimited_dimension("time").unwrap();
+
+        let var = file.add_variable::<f64>("contiguous", &["x"]).unwrap();
+        var.contiguous().unwrap();
+        var.put_values(&[1.0f64; 10], None, None).unwrap();
+
+        let var = file.add_variable::<f64>("compact", &["x"]).unwrap();
+        var.compact().unwrap();
+        var.put_values(&[2.0f64; 10], None, None).unwrap();
+
+        let var = file.add_variable::<i16>("chunked", &["time", "x"]).unwrap();
+        var.chunking(&[4, 5]).unwrap();
+
+        let var = file.add_variable::<i16>("unlimited", &["time"]).unwrap();
+        var.contiguous().unwrap_err();
+        var.compact().unwrap_err();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("contiguous").unwrap();
+    assert_eq!(var.storage().unwrap(), Storage::Contiguous);
+    assert_eq!(var.value::<f64>(Some(&[9])).unwrap(), 1.0);
+    let var = file.variable("compact").unwrap();
+    assert_eq!(var.storage().unwrap(), Storage::Compact);
+    assert_eq!(var.value::<f64>(Some(&[9])).unwrap(), 2.0);
+    assert_eq!(
+        file.variable("chunked").unwrap().storage().unwrap(),
+        Storage::Chunked(vec![4, 5])
+    );
+    assert!(matches!(
+        file.variable("unlimited").unwrap().storage().unwrap(),
+        Storage::Chunked(_)
+    ));
+}
+
+#[test]
+fn automatic_chunking() {
+    use netcdf::{ChunkStrategy, Storage};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("auto_chunk.nc");
+    let mut file = netcdf::create(&path).unwrap();
+    file.add_unlimited_dimension("time").unwrap();
+    file.add_dimension("y", 100).unwrap();
+    file.add_dimension("x", 100).unwrap();
+
+    let var = file
+        .add_variable::<f32>("series", &["time", "y", "x"])
+        .unwrap();
+    var.auto_chunk(ChunkStrategy::TimeSeries).unwrap();
+    assert_eq!(var.storage().unwrap(), Storage::Chunked(vec![1024, 16, 16]));
+
+    let var = file
+        .add_variable::<f32>("maps", &["time", "y", "x"])
+        .unwrap();
+    var.auto_chunk(ChunkStrategy::Maps).unwrap();
+    assert_eq!(var.storage().unwrap(), Storage::Chunked(vec![1, 100, 100]));
+
+    let var = file.add_variable::<f64>("balanced", &["y", "x"]).unwrap();
+    var.auto_chunk(ChunkStrategy::Balanced { target_bytes: 800 })
+        .unwrap();
+    assert_eq!(var.storage().unwrap(), Storage::Chunked(vec![10, 10]));
+
+    let var = file.add_variable::<f64>("scalar", &[]).unwrap();
+    var.auto_chunk(ChunkStrategy::Maps).unwrap_err();
+}
+
+#[test]
+fn chunk_cache() {
+    use netcdf::ChunkCache;
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("chunk_cache.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 100).unwrap();
+        let var = file.add_variable::<f64>("var", &["x"]).unwrap();
+        var.chunking(&[10]).unwrap();
+    }
+
+    let global = netcdf::chunk_cache().unwrap();
+    let cache = ChunkCache {
+        size: 1 << 24,
+        slots: 1009,
+        preemption: 0.5,
+    };
+    let file = netcdf::open_with_chunk_cache(&path, cache).unwrap();
+    let var = file.variable("var").unwrap();
+    assert_eq!(var.chunk_cache().unwrap(), cache);
+    // The global settings are restored
+    assert_eq!(netcdf::chunk_cache().unwrap(), global);
+
+    let cache = ChunkCache {
+        size: 1 << 20,
+        slots: 521,
+        preemption: 1.0,
+    };
+    var.set_chunk_cache(cache).unwrap();
+    assert_eq!(var.chunk_cache().unwrap(), cache);
+    var.set_chunk_cache(ChunkCache {
+        preemption: 2.0,
+        ..cache
+    })
+    .unwrap_err();
+}
+
+#[test]
+#[cfg(feature = "ndarray")]
+fn chunk_iteration() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("chunks.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("y", 5).unwrap();
+        file.add_dimension("x", 3).unwrap();
+        let values = (0..15).collect::<Vec<i32>>();
+
+        let var = file.add_variable::<i32>("chunked", &["y", "x"]).unwrap();
+        var.chunking(&[2, 2]).unwrap();
+        var.put_values(&values, None, None).unwrap();
+
+        let var = file.add_variable::<i32>("contiguous", &["y", "x"]).unwrap();
+        var.contiguous().unwrap();
+        var.put_values(&values, None, None).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("chunked").unwrap();
+    let chunks = var
+        .chunks::<i32>(None)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let indices = chunks.iter().map(|(i, _)| i.clone()).collect::<Vec<_>>();
+    assert_eq!(indices, [[0, 0], [0, 2], [2, 0], [2, 2], [4, 0], [4, 2]]);
+    assert_eq!(chunks[1].1.shape(), [2, 1]);
+    assert_eq!(chunks[1].1.as_slice().unwrap(), [2, 5]);
+    assert_eq!(chunks[5].1.as_slice().unwrap(), [14]);
+    let sum: i32 = chunks.iter().map(|(_, v)| v.sum()).sum();
+    assert_eq!(sum, (0..15).sum());
+
+    let var = file.variable("contiguous").unwrap();
+    assert!(var.chunks::<i32>(None).is_err());
+    assert!(var.chunks::<i32>(Some(&[0, 1])).is_err());
+    let rows = var
+        .chunks::<f64>(Some(&[1, 3]))
+        .unwrap()
+        .map(|c| c.unwrap().1.sum())
+        .collect::<Vec<_>>();
+    assert_eq!(rows, [3.0, 12.0, 21.0, 30.0, 39.0]);
+}
+
+#[test]
+fn gather_points() {
+    use netcdf::error::Error;
+    use netcdf::Conversion;
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("gather.nc");
+    let mut file = netcdf::create(path).unwrap();
+    file.add_dimension("y", 4).unwrap();
+    file.add_dimension("x", 5).unwrap();
+    let var = file.add_variable::<i32>("var", &["y", "x"]).unwrap();
+    var.put_values(&(0..20).collect::<Vec<i32>>(), None, None)
+        .unwrap();
+
+    let points: [&[usize]; 6] = [&[3, 4], &[0, 1], &[0, 2], &[3, 4], &[2, 0], &[0, 3]];
+    assert_eq!(var.gather::<i32>(&points).unwrap(), [19, 1, 2, 19, 10, 3]);
+    assert_eq!(var.gather::<f64>(&points[..2]).unwrap(), [19.0, 1.0]);
+    assert!(var.gather::<i32>(&[]).unwrap().is_empty());
+    assert_eq!(var.gather::<i32>(&[&[0]]).unwrap_err(), Error::IndexLen);
+    assert_eq!(
+        var.gather::<i32>(&[&[4, 0]]).unwrap_err(),
+        Error::IndexMismatch
+    );
+
+    var.put_values(&[300, 400], Some(&[1, 1]), Some(&[1, 2]))
+        .unwrap();
+    var.set_conversion(Conversion::Checked);
+    let points: [&[usize]; 3] = [&[1, 2], &[0, 0], &[1, 1]];
+    assert_eq!(
+        var.gather::<i8>(&points).unwrap_err(),
+        Error::OutOfRange(vec![0, 2])
+    );
+}
+
+#[test]
+fn record_writing_and_reading() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("records.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_unlimited_dimension("time").unwrap();
+        file.add_dimension("x", 3).unwrap();
+        file.add_variable::<f64>("time", &["time"]).unwrap();
+        file.add_variable::<f32>("temperature", &["x", "time"])
+            .unwrap();
+        file.add_variable::<f32>("fixed", &["x"]).unwrap();
+
+        let mut writer = file.record_writer("time").unwrap();
+        assert_eq!(writer.index(), 0);
+        writer
+            .put("time", &[0.0f64])
+            .unwrap()
+            .put("temperature", &[1.0f32, 2.0, 3.0])
+            .unwrap();
+        writer.write().unwrap();
+        assert_eq!(writer.index(), 1);
+
+        assert!(writer.put("temperature", &[1.0f32]).is_err());
+        assert!(writer.put("fixed", &[1.0f32, 2.0, 3.0]).is_err());
+        assert!(writer.put("missing", &[1.0f32]).is_err());
+        assert!(writer.write().is_err());
+        assert!(file.record_writer("x").is_err());
+    }
+    {
+        let mut file = netcdf::append(&path).unwrap();
+        let mut writer = file.record_writer("time").unwrap();
+        assert_eq!(writer.index(), 1);
+        writer
+            .put("time", &[60.0f64])
+            .unwrap()
+            .put("temperature", &[4.0f32, 5.0, 6.0])
+            .unwrap();
+        writer.write().unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    assert_eq!(file.dimension("time").unwrap().len(), 2);
+    #[cfg(feature = "ndarray")]
+    {
+        let times = file
+            .variable("time")
+            .unwrap()
+            .records::<f64>()
+            .unwrap()
+            .map(|r| r.unwrap().sum())
+            .collect::<Vec<_>>();
+        assert_eq!(times, [0.0, 60.0]);
+
+        let mut records = file
+            .variable("temperature")
+            .unwrap()
+            .records::<f32>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records.next().unwrap().unwrap().as_slice().unwrap(),
+            [1.0, 2.0, 3.0]
+        );
+        assert_eq!(
+            records.next().unwrap().unwrap().as_slice().unwrap(),
+            [4.0, 5.0, 6.0]
+        );
+        assert!(records.next().is_none());
+        assert!(file.variable("fixed").unwrap().records::<f32>().is_err());
+    }
+}
+
 #[test]
 fn set_compression_all_variables_in_a_group() {
     let d = tempfile::tempdir().expect("Could not create tempdir");
@@ -984,6 +1282,154 @@ fn set_compression_all_variables_in_a_group() {
         .unwrap_err();
 }
 
+#[test]
+fn shuffle_deflate_fletcher32() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("filters.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 10).unwrap();
+
+        let var = file.add_variable::<f32>("all", &["x"]).unwrap();
+        var.filters()
+            .shuffle(true)
+            .deflate(4)
+            .fletcher32(true)
+            .set()
+            .unwrap();
+        var.put_values(&[1.5f32; 10], None, None).unwrap();
+
+        let var = file.add_variable::<f32>("shuffled", &["x"]).unwrap();
+        var.filters().shuffle(true).set().unwrap();
+
+        file.add_variable::<f32>("plain", &["x"]).unwrap();
+
+        let var = file.add_variable::<f32>("invalid", &["x"]).unwrap();
+        var.filters()
+            .deflate(netcdf_sys::NC_MAX_DEFLATE_LEVEL + 1)
+            .set()
+            .unwrap_err();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("all").unwrap();
+    assert!(var.shuffle_value().unwrap());
+    assert_eq!(var.deflate_value().unwrap(), Some(4));
+    assert!(var.fletcher32_value().unwrap());
+    assert_eq!(var.szip_value().unwrap(), None);
+    assert_eq!(var.value::<f32>(Some(&[3])).unwrap(), 1.5);
+
+    let var = file.variable("shuffled").unwrap();
+    assert!(var.shuffle_value().unwrap());
+    assert_eq!(var.deflate_value().unwrap(), None);
+    assert!(!var.fletcher32_value().unwrap());
+
+    let var = file.variable("plain").unwrap();
+    assert!(!var.shuffle_value().unwrap());
+    assert_eq!(var.deflate_value().unwrap(), None);
+}
+
+#[test]
+#[cfg(feature = "filters")]
+fn generic_filters() {
+    use netcdf::{BloscCompressor, Filter};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("generic_filters.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 10).unwrap();
+
+        let var = file.add_variable::<i32>("filtered", &["x"]).unwrap();
+        var.chunking(&[5]).unwrap();
+        var.add_filter(&Filter::Deflate { level: 3 }).unwrap();
+        var.add_filter(&Filter::Fletcher32).unwrap();
+        var.put_values(&[7i32; 10], None, None).unwrap();
+
+        let var = file.add_variable::<i32>("unknown", &["x"]).unwrap();
+        var.chunking(&[5]).unwrap();
+        let unknown = Filter::Other {
+            id: 65000,
+            params: vec![1, 2],
+        };
+        assert!(!var.filter_available(&unknown).unwrap());
+        var.add_filter(&unknown).unwrap_err();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("filtered").unwrap();
+    assert_eq!(
+        var.filter_values().unwrap(),
+        [Filter::Deflate { level: 3 }, Filter::Fletcher32]
+    );
+    assert!(var.missing_filters().unwrap().is_empty());
+    assert_eq!(var.value::<i32>(Some(&[9])).unwrap(), 7);
+    assert!(netcdf::error::Error::MissingFilter(65000)
+        .to_string()
+        .contains("65000"));
+    assert!(file
+        .variable("unknown")
+        .unwrap()
+        .filter_values()
+        .unwrap()
+        .is_empty());
+
+    let zstd = Filter::Zstd { level: -3 };
+    assert_eq!(Filter::from_id_params(zstd.id(), &zstd.params()), zstd);
+    let blosc = Filter::Blosc {
+        compressor: BloscCompressor::Lz4,
+        level: 5,
+        shuffle: 1,
+    };
+    assert_eq!(blosc.params(), [0, 0, 0, 0, 5, 1, 1]);
+    assert_eq!(
+        Filter::from_id_params(32001, &[2, 2, 4, 40, 5, 1, 1]),
+        blosc
+    );
+    assert_eq!(
+        Filter::from_id_params(32001, &[1]),
+        Filter::Other {
+            id: 32001,
+            params: vec![1]
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "quantize")]
+fn quantization() {
+    use netcdf::Quantize;
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("quantize.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 3).unwrap();
+
+        let var = file.add_variable::<f64>("quantized", &["x"]).unwrap();
+        var.quantize(Quantize::BitGroom, 3).unwrap();
+        var.compression(5).unwrap();
+        var.put_values(&[1.23456f64, 9.87654, 5.55555], None, None)
+            .unwrap();
+
+        file.add_variable::<f32>("plain", &["x"]).unwrap();
+        let var = file.add_variable::<i32>("integer", &["x"]).unwrap();
+        var.quantize(Quantize::GranularBitRound, 3).unwrap_err();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("quantized").unwrap();
+    assert_eq!(var.quantize_value().unwrap(), Some((Quantize::BitGroom, 3)));
+    let value = var.value::<f64>(Some(&[0])).unwrap();
+    assert!((value - 1.23456).abs() < 1e-2);
+    assert_ne!(value, 1.23456);
+    assert_eq!(
+        file.variable("plain").unwrap().quantize_value().unwrap(),
+        None
+    );
+    assert!(netcdf::library_version().unwrap() >= (4, 9, 0));
+}
+
 #[test]
 #[cfg(feature = "memory")]
 fn read_from_memory() {
@@ -1238,6 +1684,42 @@ fn string_variables() {
     var.string_value(None).unwrap_err();
 }
 
+#[test]
+fn string_variables_bulk() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("string_variables_bulk.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_unlimited_dimension("x").unwrap();
+        file.add_dimension("y", 2).unwrap();
+
+        let var = file.add_string_variable("str", &["x", "y"]).unwrap();
+        var.put_strings(&["a", "b", "c", "d"], None, None).unwrap();
+        var.put_strings(&["ßæøå", ""], Some(&[3, 0]), Some(&[1, 2]))
+            .unwrap();
+        var.put_strings(&["e", "f"], Some(&[4, 0]), None).unwrap();
+        assert!(var
+            .put_strings(&["g\0"], Some(&[5, 0]), Some(&[1, 1]))
+            .is_err());
+    }
+    let file = netcdf::open(path).unwrap();
+    let var = file.variable("str").unwrap();
+
+    assert_eq!(
+        var.string_values(None, None).unwrap(),
+        vec!["a", "b", "c", "d", "", "", "ßæøå", "", "e", "f"]
+    );
+    assert_eq!(
+        var.string_values(Some(&[1, 1]), Some(&[3, 1])).unwrap(),
+        vec!["d", "", ""]
+    );
+    assert_eq!(
+        var.string_values(Some(&[4, 0]), None).unwrap(),
+        vec!["e", "f"]
+    );
+    assert_eq!(var.string_value(Some(&[3, 0])).unwrap(), "ßæøå");
+}
+
 #[test]
 fn unlimited_in_parents() {
     let d = tempfile::tempdir().expect("Could not create tempdir");
@@ -1527,3 +2009,241 @@ mod strided {
         );
     }
 }
+
+#[test]
+fn mapped_values() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("mapped.nc");
+    let mut file = netcdf::create(path).unwrap();
+
+    file.add_dimension("y", 2).unwrap();
+    file.add_dimension("x", 3).unwrap();
+    let var = file.add_variable::<i32>("var", &["y", "x"]).unwrap();
+
+    // Fortran-ordered buffer, y varies fastest
+    let fortran = [0, 3, 1, 4, 2, 5];
+    var.put_values_mapped(&fortran, None, None, &[1, 2])
+        .unwrap();
+
+    let mut buf = [0; 6];
+    var.values_to(&mut buf, None, None).unwrap();
+    assert_eq!(buf, [0, 1, 2, 3, 4, 5]);
+
+    let mut buf = [0; 6];
+    var.values_mapped_to(&mut buf, None, None, &[1, 2]).unwrap();
+    assert_eq!(buf, fortran);
+
+    // Gaps in the buffer are left untouched
+    let mut buf = [-1; 8];
+    var.values_mapped_to(&mut buf, Some(&[0, 1]), Some(&[2, 2]), &[4, 1])
+        .unwrap();
+    assert_eq!(buf, [1, 2, -1, -1, 4, 5, -1, -1]);
+
+    let mut buf = [0; 5];
+    assert_eq!(
+        var.values_mapped_to(&mut buf, None, None, &[1, 2])
+            .unwrap_err(),
+        netcdf::error::Error::BufferLen(5, 6)
+    );
+    assert_eq!(
+        var.values_mapped_to(&mut buf, None, None, &[1])
+            .unwrap_err(),
+        netcdf::error::Error::SliceLen
+    );
+}
+
+#[test]
+fn conversion_policy() {
+    use netcdf::error::Error;
+    use netcdf::Conversion;
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("conversion.nc");
+    let mut file = netcdf::create(path).unwrap();
+    file.add_dimension("x", 4).unwrap();
+    let var = file.add_variable::<i16>("var", &["x"]).unwrap();
+    assert_eq!(var.conversion(), Conversion::Lossy);
+    var.put_values(&[-1i16, 300, 5, 1000], None, None).unwrap();
+
+    let mut buf = [0i8; 4];
+    var.values_to(&mut buf[..1], Some(&[2]), Some(&[1]))
+        .unwrap();
+    assert!(var.values_to(&mut buf, None, None).is_err());
+
+    var.set_conversion(Conversion::Checked);
+    assert_eq!(
+        var.values_to(&mut buf, None, None).unwrap_err(),
+        Error::OutOfRange(vec![1, 3])
+    );
+    assert_eq!(
+        var.value::<u8>(Some(&[0])).unwrap_err(),
+        Error::OutOfRange(vec![0])
+    );
+    let mut buf = [0i8; 2];
+    assert_eq!(
+        var.values_strided_to(&mut buf, Some(&[1]), None, &[2])
+            .unwrap_err(),
+        Error::OutOfRange(vec![0, 1])
+    );
+    assert_eq!(
+        var.put_values(&[0.5f64, 4e4, -4e4, 12.0], None, None)
+            .unwrap_err(),
+        Error::OutOfRange(vec![1, 2])
+    );
+    // Nothing has been written
+    assert_eq!(var.value::<i16>(Some(&[1])).unwrap(), 300);
+    var.put_values(&[1.5f64, 2.5], None, Some(&[2])).unwrap();
+    assert_eq!(var.value::<f32>(None).unwrap(), 1.0);
+
+    var.set_conversion(Conversion::Strict);
+    assert_eq!(var.value::<i32>(None).unwrap_err(), Error::TypeMismatch);
+    assert_eq!(
+        var.put_value(1.0f64, None).unwrap_err(),
+        Error::TypeMismatch
+    );
+    assert_eq!(var.value::<i16>(None).unwrap(), 1);
+
+    // Bounds of 64-bit integers are not exact as f64
+    let var = file.add_variable::<i64>("wide", &["x"]).unwrap();
+    var.set_conversion(Conversion::Checked);
+    assert_eq!(
+        var.put_values(&[9.2e18f64, 9.3e18, -9.3e18, 0.0], None, None)
+            .unwrap_err(),
+        Error::OutOfRange(vec![1, 2])
+    );
+    assert_eq!(
+        var.put_values(&[2.0f64.powi(63)], None, Some(&[1]))
+            .unwrap_err(),
+        Error::OutOfRange(vec![0])
+    );
+
+    // Handles can be shared between threads
+    fn is_sync<T: Sync>() {}
+    is_sync::<netcdf::Variable>();
+}
+
+#[test]
+fn char_strings() {
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("char_strings.nc");
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("station", 3).unwrap();
+        let var = file
+            .add_char_string_variable("names", &["station"], 6)
+            .unwrap();
+        var.put_char_strings(&["abc", "bergen", ""], None, None)
+            .unwrap();
+        assert!(var
+            .put_char_strings(&["trondheim"], Some(&[1]), Some(&[1]))
+            .is_err());
+        var.put_char_strings(&["oslo"], Some(&[2]), Some(&[1]))
+            .unwrap();
+        assert_eq!(file.dimension("string6").unwrap().len(), 6);
+
+        // Reuses the string length dimension
+        file.add_char_string_variable("other", &[], 6).unwrap();
+        assert!(file.add_char_string_variable("empty", &[], 0).is_err());
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("names").unwrap();
+    assert_eq!(var.vartype(), netcdf_sys::NC_CHAR);
+    assert_eq!(
+        var.char_strings(None, None).unwrap(),
+        vec!["abc", "bergen", "oslo"]
+    );
+    assert_eq!(
+        var.char_strings(Some(&[1]), Some(&[2])).unwrap(),
+        vec!["bergen", "oslo"]
+    );
+    assert_eq!(var.char_value(Some(&[1, 0])).unwrap(), b'b');
+    assert_eq!(var.char_value(Some(&[0, 5])).unwrap(), 0);
+
+    let other = file.variable("other").unwrap();
+    assert_eq!(other.dimensions().len(), 1);
+    assert_eq!(other.char_strings(None, None).unwrap(), vec![""]);
+}
+
+#[test]
+#[cfg(feature = "num-complex")]
+fn complex_numbers() {
+    use netcdf::{Complex, ComplexLayout};
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("complex.nc");
+    let values = [
+        Complex::new(1.0f64, -1.0),
+        Complex::new(0.5, 2.0),
+        Complex::new(-3.0, 0.0),
+    ];
+    {
+        let mut file = netcdf::create(&path).unwrap();
+        file.add_dimension("x", 3).unwrap();
+
+        let var = file
+            .add_complex_variable::<f64>("compound", &["x"], ComplexLayout::Compound)
+            .unwrap();
+        var.put_complex_values(&values, None, None).unwrap();
+
+        let var = file
+            .add_complex_variable::<f32>("dimension", &["x"], ComplexLayout::Dimension)
+            .unwrap();
+        var.put_complex_values(&values[1..], Some(&[1]), Some(&[2]))
+            .unwrap();
+        var.put_complex_values(&[Complex::new(1.0f32, -1.0)], Some(&[0]), Some(&[1]))
+            .unwrap();
+
+        // Reuses the compound type
+        file.add_complex_variable::<f64>("other", &[], ComplexLayout::Compound)
+            .unwrap();
+        file.add_variable::<i32>("real", &["x"]).unwrap();
+        file.add_dimension("nv", 2).unwrap();
+        file.add_variable::<f64>("x_bnds", &["x", "nv"]).unwrap();
+    }
+
+    let file = netcdf::open(&path).unwrap();
+    let var = file.variable("compound").unwrap();
+    assert_eq!(var.complex_layout().unwrap(), Some(ComplexLayout::Compound));
+    let mut all = [Complex::new(0.0f64, 0.0); 3];
+    var.complex_values_to(&mut all, None, None).unwrap();
+    assert_eq!(all, values);
+    let mut buffer = [Complex::new(0.0f32, 0.0); 2];
+    var.complex_values_to(&mut buffer, Some(&[1]), Some(&[2]))
+        .unwrap();
+    assert_eq!(buffer, [Complex::new(0.5, 2.0), Complex::new(-3.0, 0.0)]);
+
+    let var = file.variable("dimension").unwrap();
+    assert_eq!(var.dimensions().len(), 2);
+    assert_eq!(
+        var.complex_layout().unwrap(),
+        Some(ComplexLayout::Dimension)
+    );
+    let mut read = [Complex::new(0.0f64, 0.0); 3];
+    var.complex_values_to(&mut read, None, None).unwrap();
+    assert_eq!(read, values);
+    var.complex_values_to(&mut read[..1], Some(&[2]), Some(&[1]))
+        .unwrap();
+    assert_eq!(read[0], Complex::new(-3.0, 0.0));
+    #[cfg(feature = "ndarray")]
+    {
+        let read = var.complex_values::<f64>(None, None).unwrap();
+        assert_eq!(read.shape(), [3]);
+        assert_eq!(read.as_slice().unwrap(), values);
+    }
+
+    let var = file.variable("other").unwrap();
+    assert_eq!(var.vartype(), file.variable("compound").unwrap().vartype());
+
+    let var = file.variable("real").unwrap();
+    assert_eq!(var.complex_layout().unwrap(), None);
+    let mut read = [Complex::new(0.0f64, 0.0); 3];
+    assert_eq!(
+        var.complex_values_to(&mut read, None, None).unwrap_err(),
+        netcdf::error::Error::TypeMismatch
+    );
+
+    // Only trailing dimensions named like nc-complex hold complex numbers
+    let var = file.variable("x_bnds").unwrap();
+    assert_eq!(var.complex_layout().unwrap(), None);
+}